@@ -51,6 +51,10 @@ INCLUDE device.x"#
         f
     };
 
+    // the ARMv8-M sub-profile cfgs are only set for some targets but checked on all of them
+    println!("cargo:rustc-check-cfg=cfg(armv8m_base)");
+    println!("cargo:rustc-check-cfg=cfg(armv8m_main)");
+
     let max_int_handlers = if target.starts_with("thumbv6m-") {
         println!("cargo:rustc-cfg=cortex_m");
         println!("cargo:rustc-cfg=armv6m");
@@ -62,6 +66,11 @@ INCLUDE device.x"#
     } else if target.starts_with("thumbv8m") {
         println!("cargo:rustc-cfg=cortex_m");
         println!("cargo:rustc-cfg=armv8m");
+        if target.starts_with("thumbv8m.base") {
+            println!("cargo:rustc-cfg=armv8m_base");
+        } else {
+            println!("cargo:rustc-cfg=armv8m_main");
+        }
         240
     } else {
         // Non ARM target. We assume you're just testing the syntax.
@@ -83,6 +92,239 @@ handlers.");
     )
     .unwrap();
 
+    if env::var_os("CARGO_FEATURE_PAINT_STACK").is_some() {
+        writeln!(
+            f,
+            r#"
+/* Paint the whole stack before calling `main` */
+__spaint = _stack_end;
+__epaint = _stack_start;
+
+ASSERT(_stack_end % 4 == 0 && _stack_start % 4 == 0, "
+ERROR(cortex-m-rt): the stack boundaries (_stack_end and _stack_start) must be 4-byte aligned");
+
+ASSERT(_stack_end <= _stack_start, "
+ERROR(cortex-m-rt): _stack_end must not be above _stack_start. If you have moved the stack to a
+different memory region using _stack_start you also need to set _stack_end");
+"#
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            f,
+            r#"
+/* Stack painting is disabled (cf. the `paint-stack` feature) */
+__spaint = 0;
+__epaint = 0;
+"#
+        )
+        .unwrap();
+    }
+
+    if env::var_os("CARGO_FEATURE_PROCESS_STACK").is_some() {
+        // CONTROL.SPSEL, plus CONTROL.nPRIV if `main` must run unprivileged
+        let control = if env::var_os("CARGO_FEATURE_UNPRIVILEGED").is_some() {
+            0b11
+        } else {
+            0b10
+        };
+
+        writeln!(
+            f,
+            r#"
+/* Run `main` on the process stack (cf. the `process-stack` feature) */
+PROVIDE(_psp_stack_size = 0x800);
+__control = {};
+
+ASSERT(_psp_stack_start % 8 == 0 && _psp_stack_size % 8 == 0, "
+ERROR(cortex-m-rt): the process stack (_psp_stack_start and _psp_stack_size) must be 8-byte
+aligned");
+"#,
+            control
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            f,
+            r#"
+/* `main` runs on the main stack (cf. the `process-stack` feature) */
+PROVIDE(_psp_stack_size = 0);
+__control = 0;
+"#
+        )
+        .unwrap();
+    }
+
+    if env::var_os("CARGO_FEATURE_RAM_VECTOR_TABLE").is_some() {
+        // ARMv6-M doesn't require VTOR, and the Cortex-M0 and the Cortex-M1 don't implement it
+        if target.starts_with("thumbv6m-") {
+            panic!("the `ram-vector-table` feature is not supported on ARMv6-M targets");
+        }
+
+        writeln!(
+            f,
+            r#"
+/* Copy the vector table to RAM and point VTOR to it (cf. the `ram-vector-table` feature) */
+__vector_table_ram_size = SIZEOF(.vector_table);
+__vector_table_ram_align = __vector_table_align;
+__vtor = __svector_table_ram;
+
+ASSERT(__svector_table_ram % __vector_table_ram_align == 0, "
+ERROR(cortex-m-rt): the RAM vector table (.vector_table_ram) must be aligned to the size of
+the vector table rounded up to the next power of two, and to at least 128 bytes; otherwise it
+can't be used as the VTOR value");
+"#
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            f,
+            r#"
+/* The vector table stays in FLASH (cf. the `ram-vector-table` feature) */
+__vector_table_ram_size = 0;
+__vector_table_ram_align = 4;
+__vtor = 0;
+"#
+        )
+        .unwrap();
+    }
+
+    if env::var_os("CARGO_FEATURE_CHAIN_LOAD").is_some() {
+        // ARMv6-M doesn't require VTOR, and the Cortex-M0 and the Cortex-M1 don't implement it
+        if target.starts_with("thumbv6m-") {
+            panic!("the `chain-load` feature is not supported on ARMv6-M targets");
+        }
+
+        writeln!(
+            f,
+            r#"
+/* Point VTOR to the vector table and reload MSP at reset (cf. the `chain-load` feature) */
+__chain_load = 1;
+"#
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            f,
+            r#"
+/* Leave VTOR and MSP as they are at reset (cf. the `chain-load` feature) */
+__chain_load = 0;
+"#
+        )
+        .unwrap();
+    }
+
+    if env::var_os("CARGO_FEATURE_EXIDX").is_some() {
+        writeln!(
+            f,
+            r#"
+/* Keep the exception handling tables in FLASH (cf. the `exidx` feature) */
+SECTIONS
+{{
+  /* ### .ARM.extab */
+  .ARM.extab : ALIGN(4)
+  {{
+    __extab_start = .;
+    *(.ARM.extab .ARM.extab.*);
+    . = ALIGN(4);
+    __extab_end = .;
+  }} > FLASH
+
+  /* ### .ARM.exidx */
+  /* Sorted by function address, as required by the unwinder */
+  .ARM.exidx : ALIGN(4)
+  {{
+    __exidx_start = .;
+    *(.ARM.exidx .ARM.exidx.*);
+    __exidx_end = .;
+  }} > FLASH
+}}
+"#
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            f,
+            r#"
+SECTIONS
+{{
+  /DISCARD/ :
+  {{
+    /* Unused exception related info that only wastes space (cf. the `exidx` feature) */
+    *(.ARM.exidx);
+    *(.ARM.exidx.*);
+    *(.ARM.extab.*);
+  }}
+}}
+"#
+        )
+        .unwrap();
+    }
+
+    // reject the priorities that don't fit in the priority bits implemented by the device. The
+    // device crate (in `device.x`) or the user (in `memory.x`) sets the number of bits; ARMv6-M
+    // devices always implement 2 of them
+    let nvic_prio_bits = if target.starts_with("thumbv6m-") {
+        2
+    } else {
+        8
+    };
+    writeln!(
+        f,
+        r#"
+/* Number of priority bits implemented by the device (cf. `#[exception(priority = ..)]`) */
+PROVIDE(__nvic_prio_bits = {});
+"#,
+        nvic_prio_bits
+    )
+    .unwrap();
+    for bits in 0..8 {
+        writeln!(
+            f,
+            r#"ASSERT(__nvic_prio_bits > {0} || __priorities_{1} == __epriorities, "
+ERROR(cortex-m-rt): a priority set using `#[exception]` or `#[interrupt]` doesn't fit in the
+priority bits implemented by the device (__nvic_prio_bits); it must be smaller than {1}");"#,
+            bits,
+            1 << bits
+        )
+        .unwrap();
+    }
+
+    // one slot per priority level, in order (cf. `.priorities` in `link.x`), with a symbol at every
+    // power of two so that the entries that need more priority bits than that can be found
+    let mut f = File::create(out.join("priorities.x")).unwrap();
+    writeln!(
+        f,
+        "/* Slots of the priority table, included by the `.priorities` section of `link.x` */"
+    )
+    .unwrap();
+    for n in 0..=255u32 {
+        if n.is_power_of_two() {
+            writeln!(f, "    __priorities_{} = .;", n).unwrap();
+        }
+        writeln!(f, "    KEEP(*(.priorities.{}));", n).unwrap();
+    }
+
+    // one slot per syscall number, in order (cf. `.syscalls` in `link.x`). An entry is 8 bytes long
+    // so a slot that holds more than that has been registered more than once
+    let mut f = File::create(out.join("syscalls.x")).unwrap();
+    writeln!(
+        f,
+        "/* Slots of the syscall table, included by the `.syscalls` section of `link.x` */"
+    )
+    .unwrap();
+    for n in 0..=255 {
+        writeln!(
+            f,
+            r#"    __syscall = .;
+    KEEP(*(.syscalls.{0}));
+    ASSERT(. - __syscall <= 8, "
+ERROR(cortex-m-rt): syscall {0} is defined more than once");"#,
+            n
+        )
+        .unwrap();
+    }
+
     println!("cargo:rustc-link-search={}", out.display());
 
     println!("cargo:rerun-if-changed=build.rs");
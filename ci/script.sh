@@ -7,6 +7,30 @@ main() {
 
     cargo check --target "$TARGET" --features device
 
+    cargo check --target "$TARGET" --features paint-stack
+
+    cargo check --target "$TARGET" --features crash-record
+
+    cargo check --target "$TARGET" --features exidx
+
+    cargo check --target "$TARGET" --features syscalls
+
+    cargo check --target "$TARGET" --features process-stack
+
+    cargo check --target "$TARGET" --features unprivileged
+
+    # ARMv6-M doesn't require VTOR, so the features that need it are rejected there
+    case $TARGET in
+        thumbv6m-*)
+            ! cargo check --target "$TARGET" --features ram-vector-table
+            ! cargo check --target "$TARGET" --features chain-load
+            ;;
+        *)
+            cargo check --target "$TARGET" --features ram-vector-table
+            cargo check --target "$TARGET" --features chain-load
+            ;;
+    esac
+
     if [ "$TARGET" = x86_64-unknown-linux-gnu ] && [ "$TRAVIS_RUST_VERSION" = stable ]; then
         ( cd macros && cargo check && cargo test )
 
@@ -15,19 +39,32 @@ main() {
 
     local examples=(
         alignment
+        backtrace
+        bootloader
         divergent-default-handler
         divergent-exception
         entry-static
+        exception-frame
+        full-exception-frame
+        hard-fault-info
+        hard-fault-recover
         main
         minimal
         override-exception
+        persist
         pre_init
+        priorities
         qemu
+        ramfunc
         state
+        svcall
+        typed-default-handler
         unsafe-default-handler
         unsafe-entry
         unsafe-exception
         unsafe-hard-fault
+        uninit
+        vector-table
     )
     local fail_examples=(
         data_overflow
@@ -57,6 +94,33 @@ main() {
             done
             cargo rustc --target "$TARGET" --example device --features device -- $linker
             cargo rustc --target "$TARGET" --example device --features device --release -- $linker
+            cargo rustc --target "$TARGET" --example handler-names --features device -- $linker
+            cargo rustc --target "$TARGET" --example handler-names --features device --release -- $linker
+            cargo rustc --target "$TARGET" --example shared-interrupt --features device -- $linker
+            cargo rustc --target "$TARGET" --example shared-interrupt --features device --release -- $linker
+            cargo rustc --target "$TARGET" --example paint-stack --features paint-stack -- $linker
+            cargo rustc --target "$TARGET" --example paint-stack --features paint-stack --release -- $linker
+            cargo rustc --target "$TARGET" --example crash-record --features crash-record -- $linker
+            cargo rustc --target "$TARGET" --example crash-record --features crash-record --release -- $linker
+            cargo rustc --target "$TARGET" --example exidx --features exidx -- $linker
+            cargo rustc --target "$TARGET" --example exidx --features exidx --release -- $linker
+            cargo rustc --target "$TARGET" --example syscalls --features syscalls -- $linker
+            cargo rustc --target "$TARGET" --example syscalls --features syscalls --release -- $linker
+            cargo rustc --target "$TARGET" --example process-stack --features process-stack -- $linker
+            cargo rustc --target "$TARGET" --example process-stack --features process-stack --release -- $linker
+            cargo rustc --target "$TARGET" --example process-stack --features unprivileged -- $linker
+            if [ "${TARGET#thumbv6m-}" = "$TARGET" ]; then
+                cargo rustc --target "$TARGET" --example ram-vector-table --features ram-vector-table -- $linker
+                cargo rustc --target "$TARGET" --example ram-vector-table --features ram-vector-table --release -- $linker
+                cargo rustc --target "$TARGET" --example chain-load --features chain-load -- $linker
+                cargo rustc --target "$TARGET" --example chain-load --features chain-load --release -- $linker
+            fi
+            cargo rustc --target "$TARGET" --example ccmram -- $linker -C link-arg=-Texamples/ccmram.x
+            cargo rustc --target "$TARGET" --example ccmram --release -- $linker -C link-arg=-Texamples/ccmram.x
+            env RUSTFLAGS="$linker -C link-arg=-Texamples/flash-config.x -C link-arg=-Tlink.x" \
+                cargo rustc --target "$TARGET" --example flash-config
+            env RUSTFLAGS="$linker -C link-arg=-Texamples/flash-config.x -C link-arg=-Tlink.x" \
+                cargo rustc --target "$TARGET" --example flash-config --release
         done
     fi
 
@@ -0,0 +1,25 @@
+//! Collecting the call stack in the `HardFault` handler
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(ef: &ExceptionFrame) -> ! {
+    let mut addresses = [0; 16];
+    for (slot, address) in addresses.iter_mut().zip(ef.backtrace()) {
+        *slot = address;
+    }
+
+    loop {}
+}
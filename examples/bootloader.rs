@@ -0,0 +1,23 @@
+//! A bootloader that starts the application placed after it in FLASH
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{boot, entry};
+
+// the bootloader occupies the first 32 KiB of FLASH
+const APP: usize = 0x8000;
+
+#[entry]
+fn main() -> ! {
+    // stay in the bootloader if there's no application, e.g. to wait for one to be uploaded
+    if boot::validate(APP).is_ok() {
+        unsafe { boot::jump_to(APP) }
+    }
+
+    loop {}
+}
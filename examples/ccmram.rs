@@ -0,0 +1,31 @@
+//! Variables in a second RAM region, initialized by `Reset` through the copy and zero tables
+//!
+//! This is a link-pass test: `ccmram.x` declares the region and registers its sections in the
+//! tables, as described in the "Initializing extra sections" section of the crate documentation.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use core::ptr;
+
+use rt::entry;
+
+#[link_section = ".ccmram.DATA"]
+static DATA: [u32; 4] = [1, 2, 3, 4];
+
+#[link_section = ".ccmbss.BSS"]
+static BSS: [u32; 4] = [0; 4];
+
+#[entry]
+fn main() -> ! {
+    unsafe {
+        assert_eq!(ptr::read_volatile(&DATA), [1, 2, 3, 4]);
+        assert_eq!(ptr::read_volatile(&BSS), [0; 4]);
+    }
+
+    loop {}
+}
@@ -0,0 +1,46 @@
+/* Linker script fragment of the `ccmram` example; CI passes it to the linker along with `link.x` */
+
+MEMORY
+{
+  CCMRAM : ORIGIN = 0x10000000, LENGTH = 8K
+}
+
+SECTIONS
+{
+    .ccmram : ALIGN(4)
+    {
+        . = ALIGN(4);
+        __sccmram = .;
+        *(.ccmram .ccmram.*);
+        . = ALIGN(4);
+        __eccmram = .;
+    } > CCMRAM AT>FLASH
+
+    .ccmbss (NOLOAD) : ALIGN(4)
+    {
+        . = ALIGN(4);
+        __sccmbss = .;
+        *(.ccmbss .ccmbss.*);
+        . = ALIGN(4);
+        __eccmbss = .;
+    } > CCMRAM
+} INSERT BEFORE .data;
+
+SECTIONS
+{
+    .copy_table.ccmram :
+    {
+        LONG(__sccmram);
+        LONG(__eccmram);
+        LONG(LOADADDR(.ccmram));
+    } > FLASH
+} INSERT AFTER .copy_table;
+
+SECTIONS
+{
+    .zero_table.ccmbss :
+    {
+        LONG(__sccmbss);
+        LONG(__eccmbss);
+    } > FLASH
+} INSERT AFTER .zero_table;
@@ -0,0 +1,23 @@
+//! An image that can be started by a bootloader
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m;
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m::Peripherals;
+use cortex_m_rt::{entry, vector_table};
+
+#[entry]
+fn main() -> ! {
+    // `Reset` has pointed VTOR to the vector table of this program, whatever the bootloader left
+    // in it
+    let vtor = Peripherals::take().unwrap().SCB.vtor.read() as usize;
+    let reset_vector = unsafe { *((vtor + 4) as *const usize) };
+    assert_eq!(reset_vector, vector_table().reset_vector());
+
+    loop {}
+}
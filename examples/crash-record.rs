@@ -0,0 +1,21 @@
+//! Retrieving the record of the last crash after a reset
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{crash, entry};
+
+#[entry]
+fn main() -> ! {
+    if let Some(record) = crash::take_last() {
+        // e.g. log the record or send it to a server
+        let _pc = record.frame().map(|frame| frame.pc());
+        let _stack = record.stack();
+    }
+
+    loop {}
+}
@@ -0,0 +1,23 @@
+//! Accessing the stacked registers from a non-maskable interrupt handler
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn NonMaskableInt(ef: &mut ExceptionFrame) {
+    static mut COUNT: u32 = 0;
+
+    *COUNT += 1;
+    let _pc = ef.pc();
+}
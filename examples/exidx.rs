@@ -0,0 +1,25 @@
+//! Looking up the unwinding information of a function
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exidx};
+
+#[entry]
+fn start() -> ! {
+    for entry in exidx::entries() {
+        let _function = entry.function_address();
+    }
+
+    if let Some(entry) = exidx::lookup(main as usize as u32) {
+        let _can_unwind = !entry.cant_unwind();
+    }
+
+    loop {}
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+//! Contents of a block of FLASH at a fixed address
+//!
+//! This is a link-pass test: `flash-config.x` reserves the block, which `.text` and `.rodata` are
+//! placed around, as described in the documentation of `_flash_config_start` and
+//! `_flash_config_end`.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+#[macro_use(flash_config)]
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use core::ptr;
+
+use rt::entry;
+
+// Flash Configuration Field of a Kinetis part; the security byte keeps the part unsecured
+flash_config! {
+    static FLASH_CONFIG: [u8; 16] = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0xFF, 0xFF,
+    ];
+}
+
+static DATA: [u32; 4] = [1, 2, 3, 4];
+
+#[entry]
+fn main() -> ! {
+    unsafe {
+        assert_eq!(ptr::read_volatile(&DATA), [1, 2, 3, 4]);
+    }
+
+    loop {}
+}
@@ -0,0 +1,8 @@
+/* Linker script fragment of the `flash-config` example; CI passes it to the linker before `link.x`
+   so that these symbols are defined by the time `link.x` lays out FLASH. `memory.x` isn't included
+   yet at that point, hence the absolute addresses: FLASH starts at 0 in the `memory.x` of the
+   examples */
+
+/* 16-byte Flash Configuration Field of Kinetis parts */
+_flash_config_start = 0x400;
+_flash_config_end = 0x410;
@@ -0,0 +1,24 @@
+//! Inspecting the callee-saved registers in the `HardFault` handler
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, FullExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(ef: &FullExceptionFrame) -> ! {
+    let _pc = ef.frame().pc();
+    let _r4 = ef.r4();
+    let _sp = ef.sp();
+
+    loop {}
+}
@@ -0,0 +1,51 @@
+//! Handlers whose names differ from the names of their vectors
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, interrupt};
+
+#[entry]
+fn main() -> ! {
+    loop {}
+}
+
+#[exception(SysTick)]
+fn tick() {
+    static mut COUNT: u32 = 0;
+
+    *COUNT += 1;
+}
+
+// one body bound to two vectors
+#[interrupt(WWDG, PVD)]
+fn supervisor() {}
+
+// interrupts portion of the vector table
+#[allow(non_camel_case_types)]
+pub enum interrupt {
+    WWDG = 0,
+    PVD = 2,
+}
+
+pub union Vector {
+    handler: unsafe extern "C" fn(),
+    reserved: usize,
+}
+
+extern "C" {
+    fn WWDG();
+    fn PVD();
+}
+
+#[link_section = ".vector_table.interrupts"]
+#[no_mangle]
+pub static __INTERRUPTS: [Vector; 3] = [
+    Vector { handler: WWDG },
+    Vector { reserved: 0 },
+    Vector { handler: PVD },
+];
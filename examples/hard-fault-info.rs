@@ -0,0 +1,26 @@
+//! Inspecting the cause of a hard fault
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame, FaultCause, FaultInfo};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(_ef: &ExceptionFrame, info: &FaultInfo) -> ! {
+    if info.has(FaultCause::PreciseDataBusError) {
+        let _address = info.bfar();
+    }
+
+    for _cause in info.causes() {}
+
+    loop {}
+}
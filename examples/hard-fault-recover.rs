@@ -0,0 +1,50 @@
+//! Recovering from a hard fault to probe whether some memory exists
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::ptr;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m_rt::{entry, exception, ExceptionFrame, FaultInfo};
+
+/// Configurable Fault Status Register
+const SCB_CFSR: *mut u32 = 0xE000_ED28 as *mut u32;
+
+static FAULTED: AtomicBool = AtomicBool::new(false);
+
+/// Returns `true` if reading from `address` doesn't fault
+fn probe(address: u32) -> bool {
+    FAULTED.store(false, Ordering::SeqCst);
+    unsafe {
+        ptr::read_volatile(address as *const u32);
+    }
+    !FAULTED.load(Ordering::SeqCst)
+}
+
+#[entry]
+fn foo() -> ! {
+    let _exists = probe(0x2000_0000);
+
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(ef: &mut ExceptionFrame, info: &FaultInfo) {
+    FAULTED.store(true, Ordering::SeqCst);
+
+    // clear the sticky fault status bits (the register doesn't exist on ARMv6-M)
+    if info.cfsr() != 0 {
+        ptr::write_volatile(SCB_CFSR, info.cfsr());
+    }
+
+    // resume execution after the faulting instruction
+    let pc = ef.pc();
+    let halfword = ptr::read_volatile(pc as *const u16);
+    let len = if halfword >> 11 >= 0b11101 { 4 } else { 2 };
+    ef.set_pc(pc + len);
+}
@@ -0,0 +1,25 @@
+//! Measures stack usage using the `paint-stack` feature.
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use core::ptr;
+
+use rt::{entry, stack};
+
+#[entry]
+fn main() -> ! {
+    let unused = stack::unused_bytes();
+    let used = stack::high_water_mark();
+    assert_eq!(used + unused, stack::size());
+
+    loop {
+        unsafe {
+            ptr::read_volatile(&used);
+        }
+    }
+}
@@ -0,0 +1,22 @@
+//! `#[persist]` variables keep their value across resets
+
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use rt::{entry, Persistent};
+
+#[entry]
+fn main() -> ! {
+    #[persist]
+    static mut BOOT_COUNT: Persistent<u32> = Persistent::uninit();
+
+    let count = BOOT_COUNT.get().cloned().unwrap_or(0);
+    BOOT_COUNT.set(count + 1);
+
+    loop {}
+}
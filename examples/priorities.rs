@@ -0,0 +1,22 @@
+//! Setting the priorities of exceptions
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+// `SysTick` can preempt `PendSV`
+#[exception(priority = 1)]
+fn SysTick() {}
+
+#[exception(priority = 2)]
+fn PendSV() {}
@@ -0,0 +1,25 @@
+//! Running `main` on the process stack
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m;
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m::register::control::{self, Spsel};
+use cortex_m_rt::{entry, exception};
+
+#[entry]
+fn main() -> ! {
+    // `main` runs on the process stack; exception handlers use the main stack
+    assert_eq!(control::read().spsel(), Spsel::Psp);
+
+    loop {}
+}
+
+#[exception]
+fn SysTick() {
+    assert_eq!(control::read().spsel(), Spsel::Msp);
+}
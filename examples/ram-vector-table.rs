@@ -0,0 +1,42 @@
+//! Installing an exception handler at runtime
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m;
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use cortex_m::peripheral::syst::SystClkSource;
+use cortex_m::Peripherals;
+use cortex_m_rt::{entry, vectors};
+
+static TICKED: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn on_tick() {
+    TICKED.store(true, Ordering::Relaxed);
+}
+
+#[entry]
+fn main() -> ! {
+    // `SysTick` has IRQ number -1
+    vectors::set_handler(-1, on_tick);
+
+    let mut syst = Peripherals::take().unwrap().SYST;
+    syst.set_clock_source(SystClkSource::Core);
+    syst.set_reload(8_000_000);
+    syst.clear_current();
+    syst.enable_counter();
+    syst.enable_interrupt();
+
+    while !TICKED.load(Ordering::Relaxed) {}
+
+    // `SysTick` is handled by `DefaultHandler` again
+    syst.disable_interrupt();
+    vectors::restore_default(-1);
+
+    loop {}
+}
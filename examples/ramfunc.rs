@@ -0,0 +1,42 @@
+//! Running functions and exception handlers from RAM
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::ptr;
+
+use cortex_m_rt::{entry, exception, ramfunc};
+
+static X: u32 = 20;
+
+#[entry]
+fn main() -> ! {
+    // calls from FLASH into RAM, which in turn calls back into FLASH
+    let x = unsafe { ptr::read_volatile(&X) };
+    assert_eq!(in_ram(x), x * 2 + 1);
+
+    loop {}
+}
+
+#[ramfunc]
+fn in_ram(x: u32) -> u32 {
+    in_flash(x * 2)
+}
+
+#[inline(never)]
+fn in_flash(x: u32) -> u32 {
+    x + 1
+}
+
+// both the handler and its trampoline are placed in RAM
+#[exception]
+#[ramfunc]
+fn SysTick() {
+    static mut TICKS: u32 = 0;
+
+    *TICKS += 1;
+}
@@ -0,0 +1,71 @@
+//! Several handlers sharing one interrupt
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    loop {}
+}
+
+// these two could live in different crates; both are called, in link order, when `PVD` fires
+mod rtc {
+    use cortex_m_rt::interrupt;
+
+    use super::interrupt;
+
+    #[interrupt(shared)]
+    fn PVD() {
+        static mut COUNT: u32 = 0;
+
+        *COUNT += 1;
+    }
+}
+
+mod comparator {
+    use cortex_m_rt::interrupt;
+
+    use super::interrupt;
+
+    #[interrupt(shared)]
+    fn PVD() {}
+
+    // a module can hold several handlers of the same interrupt
+    #[interrupt(PVD, shared)]
+    fn threshold() {}
+}
+
+// `WWDG` isn't shared, so the vector points straight to this handler
+#[cortex_m_rt::interrupt]
+fn WWDG() {}
+
+// interrupts portion of the vector table
+#[allow(non_camel_case_types)]
+pub enum interrupt {
+    WWDG = 0,
+    PVD = 2,
+}
+
+pub union Vector {
+    handler: unsafe extern "C" fn(),
+    reserved: usize,
+}
+
+extern "C" {
+    fn WWDG();
+    fn PVD();
+}
+
+#[link_section = ".vector_table.interrupts"]
+#[no_mangle]
+pub static __INTERRUPTS: [Vector; 3] = [
+    Vector { handler: WWDG },
+    Vector { reserved: 0 },
+    Vector { handler: PVD },
+];
@@ -0,0 +1,32 @@
+//! Supervisor calls that take arguments and return a value
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+fn SVCall(num: u8, frame: &mut ExceptionFrame) {
+    static mut CALLS: u32 = 0;
+
+    *CALLS += 1;
+
+    let ret = match num {
+        // return the number of supervisor calls made so far
+        0 => *CALLS,
+        // add the first two arguments
+        1 => frame.r0().wrapping_add(frame.r1()),
+        _ => u32::max_value(),
+    };
+
+    unsafe { frame.set_r0(ret) }
+}
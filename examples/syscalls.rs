@@ -0,0 +1,41 @@
+//! Registering and making supervisor calls
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+#[macro_use(svc_call)]
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_m_rt::{entry, syscall};
+
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+#[entry]
+fn foo() -> ! {
+    let _sum = svc_call!(1, 2, 3);
+    svc_call!(2);
+    let _ticks = svc_call!(3);
+
+    loop {}
+}
+
+#[syscall(1)]
+fn add(a: u32, b: u32) -> u32 {
+    a.wrapping_add(b)
+}
+
+#[syscall(2)]
+fn tick() {
+    // syscalls don't preempt each other, so this doesn't need to be an atomic read-modify-write
+    let ticks = TICKS.load(Ordering::Relaxed);
+    TICKS.store(ticks + 1, Ordering::Relaxed);
+}
+
+#[syscall(3)]
+fn ticks() -> u32 {
+    TICKS.load(Ordering::Relaxed)
+}
@@ -0,0 +1,29 @@
+//! Default handler that receives the exception or interrupt being serviced
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, Exception, ExceptionOrIrq};
+
+#[entry]
+fn foo() -> ! {
+    // thread mode
+    assert_eq!(ExceptionOrIrq::active(), None);
+
+    loop {}
+}
+
+#[exception]
+unsafe fn DefaultHandler(irq: ExceptionOrIrq) {
+    assert_eq!(ExceptionOrIrq::active(), Some(irq));
+
+    if irq == ExceptionOrIrq::Exception(Exception::PendSV) {
+        return;
+    }
+
+    loop {}
+}
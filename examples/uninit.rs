@@ -0,0 +1,23 @@
+//! `static mut` variables marked as `#[uninit]` are not initialized by the reset handler
+
+#![deny(unsafe_code)]
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt as rt;
+extern crate panic_halt;
+
+use core::mem::MaybeUninit;
+
+use rt::entry;
+
+#[entry]
+fn main() -> ! {
+    #[uninit]
+    static mut BUFFER: MaybeUninit<[u8; 1024]> = MaybeUninit::uninit();
+
+    let _buffer: &'static mut MaybeUninit<[u8; 1024]> = BUFFER;
+
+    loop {}
+}
@@ -0,0 +1,33 @@
+//! Inspecting the vector table at runtime
+
+#![deny(warnings)]
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, vector_table};
+
+#[entry]
+fn main() -> ! {
+    let table = vector_table();
+
+    // `SysTick` has a handler but `PendSV` is handled by `DefaultHandler`
+    assert!(!table.is_default(-1));
+    assert!(table.is_default(-2));
+
+    // count the interrupts nobody handles
+    let mut unhandled = 0;
+    for entry in table.entries() {
+        if entry.is_interrupt() && table.is_default(entry.irqn()) {
+            unhandled += 1;
+        }
+    }
+    assert_eq!(unhandled, table.interrupts());
+
+    loop {}
+}
+
+#[exception]
+fn SysTick() {}
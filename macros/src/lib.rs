@@ -6,12 +6,13 @@ extern crate proc_macro;
 
 use proc_macro::TokenStream;
 use proc_macro2::Span;
-use quote::quote;
+use quote::{quote, quote_spanned};
 use std::collections::HashSet;
 use std::iter;
 use syn::{
-    parse, parse_macro_input, spanned::Spanned, AttrStyle, Attribute, FnArg, Ident, Item, ItemFn,
-    ItemStatic, ReturnType, Stmt, Type, Visibility,
+    parse, parse_macro_input, spanned::Spanned, AttrStyle, Attribute, AttributeArgs, Expr, FnArg,
+    Ident, Item, ItemFn, ItemStatic, Lit, Meta, NestedMeta, PathArguments, ReturnType, Stmt, Type,
+    Visibility,
 };
 
 #[proc_macro_attribute]
@@ -50,7 +51,12 @@ pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
     }
 
     // XXX should we blacklist other attributes?
-    let (statics, stmts) = match extract_static_muts(f.block.stmts) {
+    let (mut statics, stmts) = match extract_static_muts(f.block.stmts) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
+    let sections = match extract_sections(&mut statics) {
         Err(e) => return e.to_compile_error().into(),
         Ok(x) => x,
     };
@@ -75,15 +81,20 @@ pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let resource_args = statics
         .iter()
-        .map(|statik| {
+        .zip(&sections)
+        .map(|(statik, section)| {
             let (ref cfgs, ref attrs) = extract_cfgs(statik.attrs.clone());
             let ident = &statik.ident;
             let ty = &statik.ty;
             let expr = &statik.expr;
+            let link_section = link_section(statik, *section);
+            let check = section_type_check(statik, *section);
             quote! {
                 #(#cfgs)*
                 {
+                    #check
                     #(#attrs)*
+                    #link_section
                     static mut #ident: #ty = #expr;
                     &mut #ident
                 }
@@ -124,19 +135,24 @@ enum Exception {
 #[proc_macro_attribute]
 pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut f = parse_macro_input!(input as ItemFn);
+    let args = parse_macro_input!(args as AttributeArgs);
 
-    if !args.is_empty() {
-        return parse::Error::new(Span::call_site(), "This attribute accepts no arguments")
-            .to_compile_error()
-            .into();
-    }
+    let args = match HandlerArgs::parse(args, WhiteListCaller::Exception) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
 
     if let Err(error) = check_attr_whitelist(&f.attrs, WhiteListCaller::Exception) {
         return error;
     }
 
     let fspan = f.span();
-    let ident = f.sig.ident.clone();
+    // the exception is named after the function unless its name is given as argument
+    let ident = args
+        .vectors
+        .first()
+        .cloned()
+        .unwrap_or_else(|| f.sig.ident.clone());
 
     let ident_s = ident.to_string();
     let exn = match &*ident_s {
@@ -184,6 +200,34 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
         _ => quote!(),
     };
 
+    // Only the exceptions with a configurable priority can appear in the priority table
+    let priority = match args.priority {
+        None => quote!(),
+        Some(priority) => {
+            let irqn: i16 = match &*ident_s {
+                "MemoryManagement" => -12,
+                "BusFault" => -11,
+                "UsageFault" => -10,
+                "SecureFault" => -9,
+                "SVCall" => -5,
+                "DebugMonitor" => -4,
+                "PendSV" => -2,
+                "SysTick" => -1,
+                _ => {
+                    return parse::Error::new(
+                        Span::call_site(),
+                        format!("the priority of `{}` can't be configured", ident_s),
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            };
+
+            let (ref cfgs, _) = extract_cfgs(f.attrs.clone());
+            priority_entry(cfgs, &f.sig.ident, &ident_s, quote!(#irqn), priority, false)
+        }
+    };
+
     let handler = match exn {
         Exception::DefaultHandler => {
             let valid_signature = f.sig.constness.is_none()
@@ -205,12 +249,28 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
             if !valid_signature {
                 return parse::Error::new(
                     fspan,
-                    "`DefaultHandler` must have signature `unsafe fn(i16) [-> !]`",
+                    "`DefaultHandler` must have signature `unsafe fn(i16) [-> !]` or \
+                     `unsafe fn(ExceptionOrIrq) [-> !]`",
                 )
                 .to_compile_error()
                 .into();
             }
 
+            // The handler receives either the raw IRQ number or an `ExceptionOrIrq`; the
+            // `DefaultHandlerArg` trait converts the IRQ number to the type it takes
+            let arg_ty = match f.sig.inputs[0] {
+                FnArg::Typed(ref arg) => arg.ty.clone(),
+                FnArg::Receiver(_) => {
+                    return parse::Error::new(
+                        fspan,
+                        "`DefaultHandler` must have signature `unsafe fn(i16) [-> !]` or \
+                         `unsafe fn(ExceptionOrIrq) [-> !]`",
+                    )
+                    .to_compile_error()
+                    .into();
+                }
+            };
+
             f.sig.ident = Ident::new(&format!("__cortex_m_rt_{}", f.sig.ident), Span::call_site());
             let tramp_ident = Ident::new(&format!("{}_trampoline", f.sig.ident), Span::call_site());
             let ident = &f.sig.ident;
@@ -229,39 +289,78 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 
                     let irqn = unsafe { (core::ptr::read_volatile(SCB_ICSR) & 0x1FF) as i16 - 16 };
 
-                    #ident(irqn)
+                    if unsafe { ::cortex_m_rt::dispatch_shared(irqn) } {
+                        return;
+                    }
+
+                    match <#arg_ty as ::cortex_m_rt::DefaultHandlerArg>::from_irqn(irqn) {
+                        Some(irq) => #ident(irq),
+                        // not an exception of the target; like the default `DefaultHandler`,
+                        // don't return
+                        None => loop {
+                            core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+                        },
+                    }
                 }
 
                 #f
             )
         }
         Exception::HardFault => {
+            // The HardFault handler has its own section, right after the trampoline
+            if let Some(attr) = f.attrs.iter().find(|attr| eq(attr, "ramfunc")) {
+                return parse::Error::new(
+                    attr.span(),
+                    "The `HardFault` handler can't be placed in RAM",
+                )
+                .to_compile_error()
+                .into();
+            }
+
+            // A handler that returns resumes the faulting code, so it gets to modify the frame
+            let returns = match f.sig.output {
+                ReturnType::Default => true,
+                ReturnType::Type(_, ref ty) => match **ty {
+                    Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+                    _ => false,
+                },
+            };
+
             let valid_signature = f.sig.constness.is_none()
                 && f.vis == Visibility::Inherited
                 && f.sig.abi.is_none()
-                && f.sig.inputs.len() == 1
-                && match &f.sig.inputs[0] {
-                    FnArg::Typed(arg) => match arg.ty.as_ref() {
-                        Type::Reference(r) => r.lifetime.is_none() && r.mutability.is_none(),
+                && (f.sig.inputs.len() == 1 || f.sig.inputs.len() == 2)
+                && f.sig
+                    .inputs
+                    .iter()
+                    .enumerate()
+                    .all(|(i, input)| match input {
+                        FnArg::Typed(arg) => match arg.ty.as_ref() {
+                            Type::Reference(r) => {
+                                r.lifetime.is_none()
+                                    && r.mutability.is_some() == (i == 0 && returns)
+                            }
+                            _ => false,
+                        },
                         _ => false,
-                    },
-                    _ => false,
-                }
+                    })
                 && f.sig.generics.params.is_empty()
                 && f.sig.generics.where_clause.is_none()
                 && f.sig.variadic.is_none()
                 && match f.sig.output {
-                    ReturnType::Default => false,
+                    ReturnType::Default => true,
                     ReturnType::Type(_, ref ty) => match **ty {
                         Type::Never(_) => true,
-                        _ => false,
+                        _ => returns,
                     },
                 };
 
             if !valid_signature {
                 return parse::Error::new(
                     fspan,
-                    "`HardFault` handler must have signature `unsafe fn(&ExceptionFrame) -> !`",
+                    "`HardFault` handler must have signature `unsafe fn(&ExceptionFrame) -> !` \
+                     or `unsafe fn(&ExceptionFrame, &FaultInfo) -> !`, or, if it returns, \
+                     `unsafe fn(&mut ExceptionFrame)` or `unsafe fn(&mut ExceptionFrame, &FaultInfo)`",
                 )
                 .to_compile_error()
                 .into();
@@ -271,6 +370,41 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
             let tramp_ident = Ident::new(&format!("{}_trampoline", f.sig.ident), Span::call_site());
             let ident = &f.sig.ident;
 
+            let call = if f.sig.inputs.len() == 2 {
+                quote!(#ident(frame, &::cortex_m_rt::FaultInfo::read()))
+            } else {
+                quote!(#ident(frame))
+            };
+
+            // A handler that takes a `FullExceptionFrame` is entered through
+            // `HardFaultFullTrampoline`; the linker script picks it when this marker is defined
+            let full = match f.sig.inputs[0] {
+                FnArg::Typed(ref arg) => match *arg.ty {
+                    Type::Reference(ref r) => match *r.elem {
+                        Type::Path(ref path) => path
+                            .path
+                            .segments
+                            .last()
+                            .map_or(false, |segment| segment.ident == "FullExceptionFrame"),
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                _ => false,
+            };
+            let (frame_ty, marker) = if full {
+                (
+                    quote!(::cortex_m_rt::FullExceptionFrame),
+                    quote! {
+                        #[export_name = "__cortex_m_rt_HardFault_full"]
+                        #[cfg_attr(target_os = "none", link_section = ".HardFault.full")]
+                        static FULL: [u8; 0] = [];
+                    },
+                )
+            } else {
+                (quote!(::cortex_m_rt::ExceptionFrame), quote!())
+            };
+
             let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
 
             quote!(
@@ -282,18 +416,50 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                 // because some hosted platforms (used to check the build)
                 // cannot handle the long link section names.
                 #[cfg_attr(target_os = "none", link_section = ".HardFault.user")]
-                pub unsafe extern "C" fn #tramp_ident(frame: &::cortex_m_rt::ExceptionFrame) {
-                    #ident(frame)
+                pub unsafe extern "C" fn #tramp_ident(frame: &mut #frame_ty) {
+                    #marker
+
+                    #call
                 }
 
                 #f
             )
         }
         Exception::NonMaskableInt | Exception::Other => {
+            // The handlers of these exceptions can take the stacked exception frame, which is passed
+            // to them by a trampoline
+            let takes_frame = match &*ident_s {
+                "NonMaskableInt" | "MemoryManagement" | "BusFault" | "UsageFault"
+                | "SecureFault" => true,
+                _ => false,
+            };
+            // `SVCall` also receives the immediate operand of the `svc` instruction
+            let takes_svc_number = ident_s == "SVCall";
+
+            let is_frame = |input: &FnArg| match input {
+                FnArg::Typed(arg) => match arg.ty.as_ref() {
+                    Type::Reference(r) => r.lifetime.is_none(),
+                    _ => false,
+                },
+                _ => false,
+            };
+            let is_u8 = |input: &FnArg| match input {
+                FnArg::Typed(arg) => match arg.ty.as_ref() {
+                    Type::Path(p) => p.qself.is_none() && p.path.is_ident("u8"),
+                    _ => false,
+                },
+                _ => false,
+            };
+
             let valid_signature = f.sig.constness.is_none()
                 && f.vis == Visibility::Inherited
                 && f.sig.abi.is_none()
-                && f.sig.inputs.is_empty()
+                && (f.sig.inputs.is_empty()
+                    || takes_frame && f.sig.inputs.len() == 1 && is_frame(&f.sig.inputs[0])
+                    || takes_svc_number
+                        && f.sig.inputs.len() == 2
+                        && is_u8(&f.sig.inputs[0])
+                        && is_frame(&f.sig.inputs[1]))
                 && f.sig.generics.params.is_empty()
                 && f.sig.generics.where_clause.is_none()
                 && f.sig.variadic.is_none()
@@ -307,16 +473,58 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                 };
 
             if !valid_signature {
-                return parse::Error::new(
-                    fspan,
+                let msg = if takes_frame {
+                    format!(
+                        "`{}` handler must have signature `[unsafe] fn() [-> !]` or \
+                         `[unsafe] fn(&[mut] ExceptionFrame) [-> !]`",
+                        ident_s
+                    )
+                } else if takes_svc_number {
+                    "`SVCall` handler must have signature `[unsafe] fn() [-> !]` or \
+                     `[unsafe] fn(u8, &[mut] ExceptionFrame) [-> !]`"
+                        .to_string()
+                } else {
                     "`#[exception]` handlers other than `DefaultHandler` and `HardFault` must have \
-                     signature `[unsafe] fn() [-> !]`",
-                )
-                .to_compile_error()
-                .into();
+                     signature `[unsafe] fn() [-> !]`"
+                        .to_string()
+                };
+
+                return parse::Error::new(fspan, msg).to_compile_error().into();
             }
 
-            let (statics, stmts) = match extract_static_muts(f.block.stmts) {
+            // the stacked frame is only passed to the handler if it takes it; the marker makes the
+            // linker script put the trampoline in the vector table
+            let (frame_param, frame_arg) = if f.sig.inputs.is_empty() {
+                (quote!(), quote!())
+            } else if takes_svc_number {
+                (
+                    quote!(frame: &mut ::cortex_m_rt::ExceptionFrame),
+                    quote!(::cortex_m_rt::svc_number(frame), frame,),
+                )
+            } else {
+                (
+                    quote!(frame: &mut ::cortex_m_rt::ExceptionFrame),
+                    quote!(frame,),
+                )
+            };
+            let marker = if f.sig.inputs.is_empty() {
+                quote!()
+            } else {
+                let name = format!("__cortex_m_rt_{}_frame", ident_s);
+                let section = format!(".frame.{}", ident_s);
+                quote! {
+                    #[export_name = #name]
+                    #[cfg_attr(target_os = "none", link_section = #section)]
+                    static FRAME: [u8; 0] = [];
+                }
+            };
+
+            let (mut statics, stmts) = match extract_static_muts(f.block.stmts) {
+                Err(e) => return e.to_compile_error().into(),
+                Ok(x) => x,
+            };
+
+            let sections = match extract_sections(&mut statics) {
                 Err(e) => return e.to_compile_error().into(),
                 Ok(x) => x,
             };
@@ -346,15 +554,20 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 
             let resource_args = statics
                 .iter()
-                .map(|statik| {
+                .zip(&sections)
+                .map(|(statik, section)| {
                     let (ref cfgs, ref attrs) = extract_cfgs(statik.attrs.clone());
                     let ident = &statik.ident;
                     let ty = &statik.ty;
                     let expr = &statik.expr;
+                    let link_section = link_section(statik, *section);
+                    let check = section_type_check(statik, *section);
                     quote! {
                         #(#cfgs)*
                         {
+                            #check
                             #(#attrs)*
+                            #link_section
                             static mut #ident: #ty = #expr;
                             &mut #ident
                         }
@@ -369,8 +582,11 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
                 #(#attrs)*
                 #[doc(hidden)]
                 #[export_name = #ident_s]
-                pub unsafe extern "C" fn #tramp_ident() {
+                pub unsafe extern "C" fn #tramp_ident(#frame_param) {
+                    #marker
+
                     #ident(
+                        #frame_arg
                         #(#resource_args),*
                     )
                 }
@@ -382,6 +598,7 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 
     quote!(
         #assertion
+        #priority
         #handler
     )
     .into()
@@ -390,16 +607,21 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 #[proc_macro_attribute]
 pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
     let mut f: ItemFn = syn::parse(input).expect("`#[interrupt]` must be applied to a function");
+    let args = parse_macro_input!(args as AttributeArgs);
 
-    if !args.is_empty() {
-        return parse::Error::new(Span::call_site(), "This attribute accepts no arguments")
-            .to_compile_error()
-            .into();
-    }
+    let args = match HandlerArgs::parse(args, WhiteListCaller::Interrupt) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
 
     let fspan = f.span();
-    let ident = f.sig.ident.clone();
-    let ident_s = ident.to_string();
+    // the interrupt is named after the function unless the names of the interrupts are given as
+    // arguments
+    let vectors = if args.vectors.is_empty() {
+        vec![f.sig.ident.clone()]
+    } else {
+        args.vectors.clone()
+    };
 
     // XXX should we blacklist other attributes?
 
@@ -428,11 +650,26 @@ pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
         .into();
     }
 
-    let (statics, stmts) = match extract_static_muts(f.block.stmts.iter().cloned()) {
+    let (mut statics, stmts) = match extract_static_muts(f.block.stmts.iter().cloned()) {
+        Err(e) => return e.to_compile_error().into(),
+        Ok(x) => x,
+    };
+
+    let sections = match extract_sections(&mut statics) {
         Err(e) => return e.to_compile_error().into(),
         Ok(x) => x,
     };
 
+    // The interrupts could preempt each other, so they can't share the `static mut` variables
+    if vectors.len() > 1 && !statics.is_empty() {
+        return parse::Error::new(
+            statics[0].span(),
+            "`static mut` variables can't be declared in a handler bound to several interrupts",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     f.sig.ident = Ident::new(&format!("__cortex_m_rt_{}", f.sig.ident), Span::call_site());
     f.sig.inputs.extend(statics.iter().map(|statik| {
         let ident = &statik.ident;
@@ -441,30 +678,36 @@ pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
         syn::parse::<FnArg>(quote!(#[allow(non_snake_case)] #(#attrs)* #ident: &mut #ty).into())
             .unwrap()
     }));
-    f.block.stmts = iter::once(
-        syn::parse2(quote! {{
-            // Check that this interrupt actually exists
-            interrupt::#ident;
-        }})
-        .unwrap(),
-    )
-    .chain(stmts)
-    .collect();
+    f.block.stmts = vectors
+        .iter()
+        .map(|vector| {
+            syn::parse2(quote! {{
+                // Check that this interrupt actually exists
+                interrupt::#vector;
+            }})
+            .unwrap()
+        })
+        .chain(stmts)
+        .collect();
 
-    let tramp_ident = Ident::new(&format!("{}_trampoline", f.sig.ident), Span::call_site());
     let ident = &f.sig.ident;
 
     let resource_args = statics
         .iter()
-        .map(|statik| {
+        .zip(&sections)
+        .map(|(statik, section)| {
             let (ref cfgs, ref attrs) = extract_cfgs(statik.attrs.clone());
             let ident = &statik.ident;
             let ty = &statik.ty;
             let expr = &statik.expr;
+            let link_section = link_section(statik, *section);
+            let check = section_type_check(statik, *section);
             quote! {
                 #(#cfgs)*
                 {
+                    #check
                     #(#attrs)*
+                    #link_section
                     static mut #ident: #ty = #expr;
                     &mut #ident
                 }
@@ -478,22 +721,203 @@ pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
 
     let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
 
-    quote!(
-        #(#cfgs)*
-        #(#attrs)*
-        #[doc(hidden)]
-        #[export_name = #ident_s]
-        pub unsafe extern "C" fn #tramp_ident() {
-            #ident(
-                #(#resource_args),*
+    let handlers = vectors.iter().map(|vector| {
+        let vector_s = vector.to_string();
+        let tramp_ident = if vectors.len() == 1 {
+            Ident::new(&format!("{}_trampoline", ident), Span::call_site())
+        } else {
+            Ident::new(
+                &format!("{}_{}_trampoline", ident, vector),
+                Span::call_site(),
             )
-        }
+        };
+
+        let priority = match args.priority {
+            None => quote!(),
+            Some(priority) => priority_entry(
+                cfgs,
+                ident,
+                &vector_s,
+                quote!(interrupt::#vector as i16),
+                priority,
+                args.enable,
+            ),
+        };
+
+        // A shared handler doesn't bind the vector; it's registered in the table of shared
+        // handlers, which `DefaultHandler` goes through
+        let (export_name, shared) = if args.shared {
+            let shared_ident = Ident::new(
+                &format!("__CORTEX_M_RT_{}_{}_SHARED", ident, vector_s),
+                Span::call_site(),
+            );
+
+            (
+                quote!(),
+                quote!(
+                    #(#cfgs)*
+                    #[doc(hidden)]
+                    #[allow(non_upper_case_globals)]
+                    // Only emit link_section when building for embedded targets,
+                    // because some hosted platforms (used to check the build)
+                    // cannot handle the long link section names.
+                    #[cfg_attr(target_os = "none", link_section = ".shared_interrupts")]
+                    #[used]
+                    static #shared_ident: ::cortex_m_rt::SharedHandler =
+                        ::cortex_m_rt::SharedHandler {
+                            irqn: interrupt::#vector as i16,
+                            handler: #tramp_ident,
+                        };
+                ),
+            )
+        } else {
+            (quote!(#[export_name = #vector_s]), quote!())
+        };
+
+        quote!(
+            #(#cfgs)*
+            #(#attrs)*
+            #[doc(hidden)]
+            #export_name
+            pub unsafe extern "C" fn #tramp_ident() {
+                #ident(
+                    #(#resource_args),*
+                )
+            }
+
+            #priority
+
+            #shared
+        )
+    });
+
+    quote!(
+        #(#handlers)*
 
         #f
     )
     .into()
 }
 
+/// Arguments of the `#[exception]` and `#[interrupt]` attributes
+struct HandlerArgs {
+    /// Vectors bound to the handler; if empty the vector is named after the function
+    vectors: Vec<Ident>,
+    /// Priority set before `main` is called
+    priority: Option<u8>,
+    /// Whether the interrupt is enabled before `main` is called
+    enable: bool,
+    /// Whether the handler is one of several handlers of the same interrupt
+    shared: bool,
+}
+
+impl HandlerArgs {
+    fn parse(args: AttributeArgs, caller: WhiteListCaller) -> parse::Result<Self> {
+        let is_interrupt = match caller {
+            WhiteListCaller::Interrupt => true,
+            _ => false,
+        };
+        let expected = if is_interrupt {
+            "This attribute only accepts interrupt names and the `priority = <number>`, `enable` \
+             and `shared` arguments"
+        } else {
+            "This attribute only accepts an exception name and a `priority = <number>` argument"
+        };
+
+        let mut priority = None;
+        let mut enable = false;
+        let mut shared = false;
+        let mut vectors: Vec<Ident> = vec![];
+        for arg in args {
+            match arg {
+                NestedMeta::Meta(Meta::NameValue(ref nv))
+                    if nv.path.is_ident("priority") && priority.is_none() =>
+                {
+                    priority = match nv.lit {
+                        Lit::Int(ref lit) => lit.base10_parse::<u8>().ok(),
+                        _ => None,
+                    };
+                    if priority.is_none() {
+                        return Err(parse::Error::new(
+                            nv.lit.span(),
+                            "the priority must be an integer in the range `0..=255`",
+                        ));
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(ref path))
+                    if is_interrupt && path.is_ident("enable") && !enable =>
+                {
+                    enable = true;
+                }
+                NestedMeta::Meta(Meta::Path(ref path))
+                    if is_interrupt && path.is_ident("shared") && !shared =>
+                {
+                    shared = true;
+                }
+                NestedMeta::Meta(Meta::Path(ref path))
+                    if path
+                        .get_ident()
+                        .map_or(false, |ident| !vectors.contains(ident))
+                        && (is_interrupt || vectors.is_empty()) =>
+                {
+                    vectors.push(path.get_ident().unwrap().clone());
+                }
+                _ => return Err(parse::Error::new(arg.span(), expected)),
+            }
+        }
+
+        if enable && priority.is_none() {
+            return Err(parse::Error::new(
+                Span::call_site(),
+                "`enable` requires a `priority = <number>` argument",
+            ));
+        }
+
+        Ok(HandlerArgs {
+            vectors,
+            priority,
+            enable,
+            shared,
+        })
+    }
+}
+
+/// Emits an entry of the priority table
+///
+/// The entry is named after both the handler and the vector, so that several handlers of the same
+/// vector (e.g. shared interrupt handlers) can be declared in the same module
+fn priority_entry(
+    cfgs: &[Attribute],
+    handler: &Ident,
+    vector: &str,
+    irqn: proc_macro2::TokenStream,
+    priority: u8,
+    enable: bool,
+) -> proc_macro2::TokenStream {
+    let ident = Ident::new(
+        &format!("__CORTEX_M_RT_{}_{}_PRIORITY", handler, vector),
+        Span::call_site(),
+    );
+    // the entries are sorted by priority at link time (cf. `priorities.x`)
+    let section = format!(".priorities.{}", priority);
+
+    quote!(
+        #(#cfgs)*
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        // Only emit link_section when building for embedded targets,
+        // because some hosted platforms (used to check the build)
+        // cannot handle the long link section names.
+        #[cfg_attr(target_os = "none", link_section = #section)]
+        #[used]
+        static #ident: ::cortex_m_rt::Priority = ::cortex_m_rt::Priority {
+            irqn: #irqn,
+            priority: #priority,
+            enable: #enable,
+        };
+    )
+}
+
 #[proc_macro_attribute]
 pub fn pre_init(args: TokenStream, input: TokenStream) -> TokenStream {
     let f = parse_macro_input!(input as ItemFn);
@@ -534,6 +958,16 @@ pub fn pre_init(args: TokenStream, input: TokenStream) -> TokenStream {
         return error;
     }
 
+    // RAM hasn't been initialized yet when `__pre_init` runs
+    if let Some(attr) = f.attrs.iter().find(|attr| eq(attr, "ramfunc")) {
+        return parse::Error::new(
+            attr.span(),
+            "`#[pre_init]` functions can't be placed in RAM",
+        )
+        .to_compile_error()
+        .into();
+    }
+
     // XXX should we blacklist other attributes?
     let attrs = f.attrs;
     let ident = f.sig.ident;
@@ -548,6 +982,155 @@ pub fn pre_init(args: TokenStream, input: TokenStream) -> TokenStream {
     .into()
 }
 
+#[proc_macro_attribute]
+pub fn syscall(args: TokenStream, input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as ItemFn);
+
+    let number = match syn::parse::<syn::LitInt>(args)
+        .ok()
+        .and_then(|lit| lit.base10_parse::<u8>().ok())
+    {
+        Some(number) => number,
+        None => {
+            return parse::Error::new(
+                Span::call_site(),
+                "`#[syscall]` requires the syscall number, an integer in the range `0..=255`",
+            )
+            .to_compile_error()
+            .into();
+        }
+    };
+
+    let is_u32 = |ty: &Type| match ty {
+        Type::Path(p) => p.qself.is_none() && p.path.is_ident("u32"),
+        _ => false,
+    };
+    let returns_u32 = match f.sig.output {
+        ReturnType::Default => false,
+        ReturnType::Type(_, ref ty) => is_u32(ty),
+    };
+
+    // check the function signature; `svc_call!` is safe to use, so the function must be safe too
+    let valid_signature = f.sig.constness.is_none()
+        && f.sig.asyncness.is_none()
+        && f.sig.unsafety.is_none()
+        && f.sig.abi.is_none()
+        && f.sig.inputs.len() <= 4
+        && f.sig.inputs.iter().all(|input| match input {
+            FnArg::Typed(arg) => is_u32(&arg.ty),
+            _ => false,
+        })
+        && f.sig.generics.params.is_empty()
+        && f.sig.generics.where_clause.is_none()
+        && f.sig.variadic.is_none()
+        && match f.sig.output {
+            ReturnType::Default => true,
+            ReturnType::Type(_, ref ty) => match **ty {
+                Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+                _ => returns_u32,
+            },
+        };
+
+    if !valid_signature {
+        return parse::Error::new(
+            f.span(),
+            "`#[syscall]` function must have signature `fn([u32, ..]) [-> u32]` with at most four \
+             arguments",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    if let Err(error) = check_attr_whitelist(&f.attrs, WhiteListCaller::Syscall) {
+        return error;
+    }
+
+    let ident = &f.sig.ident;
+    let tramp_ident = Ident::new(
+        &format!("__cortex_m_rt_{}_syscall", ident),
+        Span::call_site(),
+    );
+    let entry_ident = Ident::new(
+        &format!("__CORTEX_M_RT_SYSCALL_{}", ident),
+        Span::call_site(),
+    );
+    let section = format!(".syscalls.{}", number);
+    let number = u32::from(number);
+
+    // the arguments are passed in r0-r3; the ones the function doesn't take are ignored
+    let args = ["a0", "a1", "a2", "a3"]
+        .iter()
+        .take(f.sig.inputs.len())
+        .map(|arg| Ident::new(arg, Span::call_site()))
+        .collect::<Vec<_>>();
+    let call = if returns_u32 {
+        quote!(#ident(#(#args),*))
+    } else {
+        quote!(#ident(#(#args),*); 0)
+    };
+
+    let (ref cfgs, _) = extract_cfgs(f.attrs.clone());
+
+    quote!(
+        #f
+
+        #(#cfgs)*
+        #[doc(hidden)]
+        #[allow(unused_variables)]
+        unsafe extern "C" fn #tramp_ident(a0: u32, a1: u32, a2: u32, a3: u32) -> u32 {
+            #call
+        }
+
+        #(#cfgs)*
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        // Only emit link_section when building for embedded targets,
+        // because some hosted platforms (used to check the build)
+        // cannot handle the long link section names.
+        #[cfg_attr(target_os = "none", link_section = #section)]
+        #[used]
+        static #entry_ident: ::cortex_m_rt::Syscall = ::cortex_m_rt::Syscall {
+            number: #number,
+            handler: #tramp_ident,
+        };
+    )
+    .into()
+}
+
+#[proc_macro_attribute]
+pub fn ramfunc(args: TokenStream, input: TokenStream) -> TokenStream {
+    let mut f = parse_macro_input!(input as ItemFn);
+
+    if !args.is_empty() {
+        return parse::Error::new(Span::call_site(), "This attribute accepts no arguments")
+            .to_compile_error()
+            .into();
+    }
+
+    let valid_signature = f.sig.constness.is_none() && f.sig.asyncness.is_none();
+
+    if !valid_signature {
+        return parse::Error::new(
+            f.span(),
+            "`#[ramfunc]` can't be applied to `const` or `async` functions",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let section = format!(".ramfunc.{}", f.sig.ident);
+
+    // The function must not be inlined into its callers, which are most likely in FLASH
+    f.attrs.push(syn::parse_quote!(#[inline(never)]));
+    // Only emit link_section when building for embedded targets,
+    // because some hosted platforms (used to check the build)
+    // cannot handle the long link section names.
+    f.attrs
+        .push(syn::parse_quote!(#[cfg_attr(target_os = "none", link_section = #section)]));
+
+    quote!(#f).into()
+}
+
 /// Extracts `static mut` vars from the beginning of the given statements
 fn extract_static_muts(
     stmts: impl IntoIterator<Item = Stmt>,
@@ -586,6 +1169,192 @@ fn extract_static_muts(
     Ok((statics, stmts))
 }
 
+#[derive(Clone, Copy)]
+enum Section {
+    Uninit,
+    Persist,
+}
+
+/// Removes the `#[uninit]` and `#[persist]` attributes from the given `static mut` variables
+///
+/// Returns the section each variable must be placed in, if any
+fn extract_sections(statics: &mut [ItemStatic]) -> Result<Vec<Option<Section>>, parse::Error> {
+    let mut sections = vec![];
+    for statik in statics {
+        let mut section = None;
+        let mut attrs = vec![];
+        for attr in statik.attrs.drain(..) {
+            let this = if eq(&attr, "uninit") {
+                Section::Uninit
+            } else if eq(&attr, "persist") {
+                Section::Persist
+            } else {
+                attrs.push(attr);
+                continue;
+            };
+
+            if section.is_some() {
+                return Err(parse::Error::new(
+                    attr.span(),
+                    "a variable can only be placed in one section",
+                ));
+            }
+
+            section = Some(this);
+        }
+        statik.attrs = attrs;
+
+        match section {
+            Some(Section::Uninit) => {
+                if !is_maybe_uninit(&statik.ty) {
+                    return Err(parse::Error::new(
+                        statik.ty.span(),
+                        "`#[uninit]` variables must have type `MaybeUninit<T>` or \
+                         `[MaybeUninit<T>; N]`",
+                    ));
+                }
+
+                if !is_uninit_call(&statik.expr) {
+                    return Err(parse::Error::new(
+                        statik.expr.span(),
+                        "`#[uninit]` variables must be initialized with `MaybeUninit::uninit()`",
+                    ));
+                }
+            }
+            Some(Section::Persist) => {
+                if !is_generic_type(&statik.ty, "Persistent") {
+                    return Err(parse::Error::new(
+                        statik.ty.span(),
+                        "`#[persist]` variables must have type `Persistent<T>`",
+                    ));
+                }
+
+                if !is_uninit_call(&statik.expr) {
+                    return Err(parse::Error::new(
+                        statik.expr.span(),
+                        "`#[persist]` variables must be initialized with `Persistent::uninit()`",
+                    ));
+                }
+            }
+            None => {}
+        }
+
+        sections.push(section);
+    }
+
+    Ok(sections)
+}
+
+/// Returns `true` if `ty` is the generic type `name<T>`
+fn is_generic_type(ty: &Type, name: &str) -> bool {
+    match ty {
+        Type::Path(path) => {
+            path.qself.is_none()
+                && match path.path.segments.last() {
+                    Some(segment) => {
+                        segment.ident == name
+                            && match segment.arguments {
+                                PathArguments::AngleBracketed(ref args) => args.args.len() == 1,
+                                _ => false,
+                            }
+                    }
+                    None => false,
+                }
+        }
+        Type::Group(group) => is_generic_type(&group.elem, name),
+        Type::Paren(paren) => is_generic_type(&paren.elem, name),
+        _ => false,
+    }
+}
+
+/// Returns `true` if `ty` is `MaybeUninit<T>` or an array of those
+fn is_maybe_uninit(ty: &Type) -> bool {
+    match ty {
+        Type::Array(array) => is_maybe_uninit(&array.elem),
+        _ => is_generic_type(ty, "MaybeUninit"),
+    }
+}
+
+/// Returns `true` if `expr` is a call to an `uninit()` constructor, or an array of those
+fn is_uninit_call(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => {
+            call.args.is_empty()
+                && match *call.func {
+                    Expr::Path(ref path) => match path.path.segments.last() {
+                        Some(segment) => segment.ident == "uninit",
+                        None => false,
+                    },
+                    _ => false,
+                }
+        }
+        Expr::Repeat(repeat) => is_uninit_call(&repeat.expr),
+        Expr::Group(group) => is_uninit_call(&group.expr),
+        Expr::Paren(paren) => is_uninit_call(&paren.expr),
+        _ => false,
+    }
+}
+
+/// Returns an item that only type checks if the type of the `static mut` variable really is
+/// `core::mem::MaybeUninit<T>` (or an array of those) or `cortex_m_rt::Persistent<T>`
+///
+/// `extract_sections` only looks at the last segment of the type path, which a user type with the
+/// same name would also pass.
+fn section_type_check(statik: &ItemStatic, section: Option<Section>) -> proc_macro2::TokenStream {
+    let path = match section {
+        Some(Section::Uninit) => quote!(::core::mem::MaybeUninit),
+        Some(Section::Persist) => quote!(::cortex_m_rt::Persistent),
+        None => return quote!(),
+    };
+
+    let ty = &statik.ty;
+    let expected = replace_generic_type(ty, &path);
+
+    quote_spanned! {ty.span()=>
+        #[allow(dead_code)]
+        fn __cortex_m_rt_check_section_type(x: #ty) -> #expected {
+            x
+        }
+    }
+}
+
+/// Rebuilds `ty`, a generic type `name<T>` or an array of those, with `path` in place of `name`
+fn replace_generic_type(ty: &Type, path: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match ty {
+        Type::Array(array) => {
+            let elem = replace_generic_type(&array.elem, path);
+            let len = &array.len;
+            quote!([#elem; #len])
+        }
+        Type::Path(type_path) => {
+            let args = type_path
+                .path
+                .segments
+                .last()
+                .map(|segment| &segment.arguments);
+            quote!(#path #args)
+        }
+        Type::Group(group) => replace_generic_type(&group.elem, path),
+        Type::Paren(paren) => replace_generic_type(&paren.elem, path),
+        _ => quote!(#ty),
+    }
+}
+
+/// Returns the attribute that places a `static mut` variable in the given section
+fn link_section(statik: &ItemStatic, section: Option<Section>) -> proc_macro2::TokenStream {
+    let section = match section {
+        Some(Section::Uninit) => format!(".uninit.{}", statik.ident),
+        Some(Section::Persist) => format!(".persist.{}", statik.ident),
+        None => return quote!(),
+    };
+
+    quote! {
+        // Only emit link_section when building for embedded targets, because some hosted
+        // platforms (used to check the build) cannot handle the long link section names.
+        #[cfg_attr(target_os = "none", link_section = #section)]
+    }
+}
+
 fn extract_cfgs(attrs: Vec<Attribute>) -> (Vec<Attribute>, Vec<Attribute>) {
     let mut cfgs = vec![];
     let mut not_cfgs = vec![];
@@ -606,6 +1375,7 @@ enum WhiteListCaller {
     Exception,
     Interrupt,
     PreInit,
+    Syscall,
 }
 
 fn check_attr_whitelist(attrs: &[Attribute], caller: WhiteListCaller) -> Result<(), TokenStream> {
@@ -618,6 +1388,7 @@ fn check_attr_whitelist(attrs: &[Attribute], caller: WhiteListCaller) -> Result<
         "deny",
         "forbid",
         "cold",
+        "ramfunc",
     ];
 
     'o: for attr in attrs {
@@ -638,6 +1409,9 @@ fn check_attr_whitelist(attrs: &[Attribute], caller: WhiteListCaller) -> Result<
             WhiteListCaller::PreInit => {
                 "this attribute is not allowed on a pre-init controlled by cortex-m-rt"
             }
+            WhiteListCaller::Syscall => {
+                "this attribute is not allowed on a syscall controlled by cortex-m-rt"
+            }
         };
 
         return Err(parse::Error::new(attr.span(), &err_str)
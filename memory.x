@@ -18,6 +18,15 @@ MEMORY
    By default it will be placed at the end of the RAM region */
 /* _stack_start = ORIGIN(CCRAM) + LENGTH(CCRAM); */
 
+/* The location of the vector table can be overridden using the `_vector_table_start` symbol.
+   By default it will be placed at the start of the FLASH region */
+/* _vector_table_start = ORIGIN(FLASH) + 0x4000; */
+
+/* A block of FLASH at a fixed address can be reserved using the `_flash_config_start` and
+   `_flash_config_end` symbols; .text and .rodata are placed around it */
+/* _flash_config_start = ORIGIN(FLASH) + 0x400; */
+/* _flash_config_end = ORIGIN(FLASH) + 0x410; */
+
 /* The location of the .text section can be overridden using the `_stext` symbol.
    By default it will place after .vector_table */
 /* _stext = ORIGIN(FLASH) + 0x40c; */
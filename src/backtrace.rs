@@ -0,0 +1,165 @@
+//! Heuristic stack unwinding
+
+use core::ptr;
+
+use ExceptionFrame;
+
+/// Iterator over the call stack of the code preempted by an exception
+///
+/// This `struct` is created by [`ExceptionFrame::backtrace`].
+///
+/// [`ExceptionFrame::backtrace`]: struct.ExceptionFrame.html#method.backtrace
+///
+/// The first item is the stacked Program Counter, i.e. the address of the instruction that was
+/// being executed when the exception was taken. The following items are the addresses of the call
+/// instructions (`BL` or `BLX`) that led to it, from the innermost to the outermost call. These can
+/// be turned into function names and line numbers by host tooling like `addr2line`.
+///
+/// The call stack is recovered without debug information: the stacked Link Register and every word
+/// on the stack above the exception frame are checked, and a value is reported when it looks like
+/// a return address, that is when it has the Thumb bit set, it points into `.text` (between the
+/// `__stext` and `__etext` symbols) and the instruction that precedes it is a call instruction.
+/// This means that stale return addresses left in the stack frame of a function, or data words that
+/// happen to look like return addresses, may be reported as well, so the output should be treated
+/// as a list of candidates. Consecutive identical return addresses are only reported once.
+///
+/// The stack is only scanned if the exception frame is located in the stack (between `_stack_end`
+/// and `_stack_start`) or in the statically allocated RAM (between the start of `.persist` and the
+/// end of `.uninit`), which rules out reading memory that doesn't exist; otherwise only the Program
+/// Counter and the Link Register are checked.
+///
+/// The iterator holds a copy of the exception frame, so it doesn't borrow the frame it was created
+/// from.
+pub struct Backtrace {
+    frame: ExceptionFrame,
+    // number of the stacked registers (PC, then LR) checked so far
+    registers: u8,
+    // next stack word to check
+    sp: u32,
+    // end of the memory region that contains the stack
+    end: u32,
+    // last return address that was reported
+    last: u32,
+}
+
+impl Backtrace {
+    pub(crate) fn new(frame: &ExceptionFrame) -> Self {
+        let addr = frame as *const ExceptionFrame as u32;
+        let sp = addr + 32;
+        let end = match ram_region(addr) {
+            Some((_, end)) => end,
+            None => sp,
+        };
+
+        Backtrace {
+            frame: *frame,
+            registers: 0,
+            sp,
+            end,
+            last: 0,
+        }
+    }
+}
+
+impl Iterator for Backtrace {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.registers == 0 {
+            self.registers = 1;
+            return Some(self.frame.pc());
+        }
+
+        if self.registers == 1 {
+            self.registers = 2;
+            let lr = self.frame.lr();
+            if let Some(call) = call_site(lr) {
+                self.last = lr;
+                return Some(call);
+            }
+        }
+
+        while self.sp + 4 <= self.end {
+            let word = unsafe { ptr::read_volatile(self.sp as *const u32) };
+            self.sp += 4;
+
+            if word != self.last {
+                if let Some(call) = call_site(word) {
+                    self.last = word;
+                    return Some(call);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Returns the bounds of the RAM region that contains `addr`, if it's one of the regions known to
+/// the linker script
+///
+/// The regions checked are the stack and the statically allocated RAM. Reading memory within the
+/// returned bounds won't fault.
+pub(crate) fn ram_region(addr: u32) -> Option<(u32, u32)> {
+    extern "C" {
+        static __spersist: u32;
+        static __sheap: u32;
+        static _stack_end: u32;
+        static _stack_start: u32;
+    }
+
+    let regions = unsafe {
+        [
+            (
+                &_stack_end as *const u32 as u32,
+                &_stack_start as *const u32 as u32,
+            ),
+            (
+                &__spersist as *const u32 as u32,
+                &__sheap as *const u32 as u32,
+            ),
+        ]
+    };
+
+    regions
+        .iter()
+        .cloned()
+        .find(|&(start, end)| addr & 3 == 0 && start <= addr && addr < end)
+}
+
+/// If `ret` looks like a return address, returns the address of the call instruction that produced
+/// it
+fn call_site(ret: u32) -> Option<u32> {
+    extern "C" {
+        static __stext: u16;
+        static __etext: u16;
+    }
+
+    // return addresses always have the Thumb bit set
+    if ret & 1 == 0 {
+        return None;
+    }
+    let ret = ret & !1;
+
+    let (stext, etext) = unsafe { (&__stext as *const u16 as u32, &__etext as *const u16 as u32) };
+    if ret > etext {
+        return None;
+    }
+
+    let halfword = |addr: u32| unsafe { ptr::read_volatile(addr as *const u16) };
+
+    // BL <label> (32-bit, encoding T1)
+    if ret >= stext + 4
+        && halfword(ret - 4) & 0xf800 == 0xf000
+        && halfword(ret - 2) & 0xd000 == 0xd000
+    {
+        return Some(ret - 4);
+    }
+
+    // BLX <Rm> (16-bit)
+    if ret >= stext + 2 && halfword(ret - 2) & 0xff87 == 0x4780 {
+        return Some(ret - 2);
+    }
+
+    None
+}
@@ -0,0 +1,142 @@
+//! Starting another image
+//!
+//! A bootloader built on `cortex-m-rt` hands control over to another image, e.g. an application
+//! that's also built on `cortex-m-rt`, using [`jump_to`](fn.jump_to.html). The other image starts
+//! running as if the device had booted it directly: its `Reset` handler runs on a fresh main stack,
+//! with VTOR pointing to its vector table and with no interrupt enabled or pending.
+//!
+//! # Examples
+//!
+//! ``` ignore
+//! use cortex_m_rt::boot;
+//!
+//! // the application is placed right after the 32 KiB the bootloader occupies
+//! const APP: usize = 0x0800_8000;
+//!
+//! if boot::validate(APP).is_ok() {
+//!     unsafe { boot::jump_to(APP) }
+//! }
+//! ```
+
+use core::ptr;
+
+/// Interrupt Control and State Register
+const SCB_ICSR: usize = 0xE000_ED04;
+
+/// SysTick Control and Status Register
+const SYST_CSR: usize = 0xE000_E010;
+
+/// Interrupt Clear-Enable Registers
+const NVIC_ICER: usize = 0xE000_E180;
+
+/// Interrupt Clear-Pending Registers
+const NVIC_ICPR: usize = 0xE000_E280;
+
+/// Number of ICER / ICPR registers; each covers 32 interrupts
+#[cfg(armv6m)]
+const NVIC_REGISTERS: usize = 1;
+#[cfg(not(armv6m))]
+const NVIC_REGISTERS: usize = 16;
+
+/// Why an image can't be started
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidImage {
+    /// The vector table is not 128-byte aligned, so VTOR can't point to it
+    MisalignedVectorTable,
+
+    /// The initial stack pointer, the first word of the vector table, is not in `RAM`; this is the
+    /// value found there
+    StackPointer(u32),
+
+    /// The reset vector, the second word of the vector table, is not the address of Thumb code in
+    /// `FLASH`; this is the value found there
+    ResetVector(u32),
+}
+
+/// Checks that there's an image whose vector table is at address `vector_table`
+///
+/// The checks are done against the `FLASH` and `RAM` regions of the calling program, as declared
+/// in its `memory.x`: the initial stack pointer must be in `RAM` (it may be the end of the region)
+/// and 4-byte aligned, and the reset vector must have its Thumb bit set and point into `FLASH`.
+/// This catches erased or partially written images, but it doesn't verify their contents.
+pub fn validate(vector_table: usize) -> Result<(), InvalidImage> {
+    extern "C" {
+        static __sflash: u8;
+        static __eflash: u8;
+        static __sram: u8;
+        static __eram: u8;
+    }
+
+    if vector_table & 0x7F != 0 {
+        return Err(InvalidImage::MisalignedVectorTable);
+    }
+
+    let (sflash, eflash, sram, eram) = unsafe {
+        (
+            &__sflash as *const u8 as usize,
+            &__eflash as *const u8 as usize,
+            &__sram as *const u8 as usize,
+            &__eram as *const u8 as usize,
+        )
+    };
+
+    let sp = unsafe { ptr::read_volatile(vector_table as *const u32) };
+    if (sp as usize) <= sram || (sp as usize) > eram || sp & 0b11 != 0 {
+        return Err(InvalidImage::StackPointer(sp));
+    }
+
+    let reset = unsafe { ptr::read_volatile((vector_table + 4) as *const u32) };
+    let address = (reset & !1) as usize;
+    if reset & 1 == 0 || address < sflash || address >= eflash {
+        return Err(InvalidImage::ResetVector(reset));
+    }
+
+    Ok(())
+}
+
+/// Starts the image whose vector table is at address `vector_table`
+///
+/// After checking the image using [`validate`](fn.validate.html) this stops SysTick, disables all
+/// the interrupts in the NVIC and clears their pending state, as well as the pending state of
+/// `SysTick` and `PendSV`. It then points VTOR to the vector table, switches to the main stack in
+/// privileged mode, loads MSP with the initial stack pointer, unmasks interrupts (clears PRIMASK)
+/// and branches to the reset vector. The stack of the caller is abandoned.
+///
+/// # Panics
+///
+/// This function panics if the image is not valid (see [`validate`](fn.validate.html)) or if it's
+/// called from an exception handler.
+///
+/// # Safety
+///
+/// This function must be called in privileged mode, on a core that implements VTOR (i.e. not the
+/// Cortex-M0). Only the core peripherals mentioned above are reset: any other peripheral the caller
+/// has set up (e.g. DMA transfers, clocks, the MPU) is left as it is, and the image must be able to
+/// cope with that.
+pub unsafe fn jump_to(vector_table: usize) -> ! {
+    extern "C" {
+        fn __cortex_m_rt_ipsr() -> u32;
+        fn __cortex_m_rt_jump(vector_table: usize) -> !;
+    }
+
+    if let Err(error) = validate(vector_table) {
+        panic!("can't start the image at {:#x}: {:?}", vector_table, error);
+    }
+
+    if __cortex_m_rt_ipsr() & 0x1FF != 0 {
+        panic!("`jump_to` can't be called from an exception handler");
+    }
+
+    // stop SysTick first, so it doesn't fire while the NVIC is being cleaned up
+    ptr::write_volatile(SYST_CSR as *mut u32, 0);
+
+    for i in 0..NVIC_REGISTERS {
+        ptr::write_volatile((NVIC_ICER as *mut u32).add(i), !0);
+        ptr::write_volatile((NVIC_ICPR as *mut u32).add(i), !0);
+    }
+
+    // PENDSTCLR and PENDSVCLR
+    ptr::write_volatile(SCB_ICSR as *mut u32, (1 << 25) | (1 << 27));
+
+    __cortex_m_rt_jump(vector_table)
+}
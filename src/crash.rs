@@ -0,0 +1,305 @@
+//! Crash records that survive a reset
+//!
+//! When the `crash-record` feature is enabled the default `HardFault` handler saves the stacked
+//! registers, the fault status registers and a snapshot of the top of the stack in a
+//! [`CrashRecord`] and then resets the device. After the reset the program can retrieve the record,
+//! exactly once, using [`take_last`] and, for example, log it or send it somewhere for analysis.
+//!
+//! [`CrashRecord`]: struct.CrashRecord.html
+//! [`take_last`]: fn.take_last.html
+//!
+//! The record is kept in the `.persist` section (see [`Persistent`]), so it survives a warm reset
+//! but not a power cycle.
+//!
+//! [`Persistent`]: ../struct.Persistent.html
+//!
+//! A user defined `HardFault` handler can save a record itself using [`save_hard_fault`]. Panics
+//! can be recorded as well by calling [`save_panic`] from the panic handler:
+//!
+//! [`save_hard_fault`]: fn.save_hard_fault.html
+//! [`save_panic`]: fn.save_panic.html
+//!
+//! ``` ignore
+//! use core::panic::PanicInfo;
+//! use cortex_m_rt::crash;
+//!
+//! #[panic_handler]
+//! fn panic(info: &PanicInfo) -> ! {
+//!     crash::save_panic(info);
+//!     crash::reset()
+//! }
+//! ```
+
+use core::fmt::{self, Write};
+use core::panic::PanicInfo;
+use core::sync::atomic::{self, Ordering};
+use core::{cmp, ptr, str};
+
+use backtrace::ram_region;
+use {ExceptionFrame, FaultInfo, Persistent, PersistentData};
+
+/// Maximum number of words of stack saved in a `CrashRecord`
+pub const STACK_WORDS: usize = 32;
+
+/// Maximum length, in bytes, of the file name of a panic location
+const FILE_LEN: usize = 64;
+/// Maximum length, in bytes, of a panic message
+const MESSAGE_LEN: usize = 128;
+
+const KIND_HARD_FAULT: u32 = 1;
+const KIND_PANIC: u32 = 2;
+
+/// Application Interrupt and Reset Control Register
+const SCB_AIRCR: *mut u32 = 0xE000_ED0C as *mut u32;
+const AIRCR_VECTKEY: u32 = 0x05FA << 16;
+const AIRCR_PRIGROUP_MASK: u32 = 0x7 << 8;
+const AIRCR_SYSRESETREQ: u32 = 1 << 2;
+
+#[cfg_attr(cortex_m, link_section = ".persist.cortex_m_rt.CRASH")]
+static mut CRASH: Persistent<CrashRecord> = Persistent::uninit();
+
+/// Pointer to the crash record; `CRASH` is only accessed through it
+fn crash() -> *mut Persistent<CrashRecord> {
+    unsafe { &mut CRASH as *mut Persistent<CrashRecord> }
+}
+
+/// What caused a crash
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CrashKind {
+    /// A HardFault exception
+    HardFault,
+    /// A panic
+    Panic,
+}
+
+/// Information about a crash, saved before the reset that followed it
+///
+/// All fields are plain integers and bytes, so a record left in RAM by a different version of the
+/// program can't cause undefined behavior; at worst it will contain meaningless data.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct CrashRecord {
+    kind: u32,
+    frame: ExceptionFrame,
+    fault: FaultInfo,
+    stack_address: u32,
+    stack_len: u32,
+    stack: [u32; STACK_WORDS],
+    line: u32,
+    column: u32,
+    file_len: u32,
+    file: [u8; FILE_LEN],
+    message_len: u32,
+    message: [u8; MESSAGE_LEN],
+}
+
+// all the fields are 4-byte integers or arrays of bytes whose length is a multiple of 4
+unsafe impl PersistentData for CrashRecord {}
+
+impl CrashRecord {
+    /// Returns what caused the crash
+    pub fn kind(&self) -> CrashKind {
+        if self.kind == KIND_HARD_FAULT {
+            CrashKind::HardFault
+        } else {
+            CrashKind::Panic
+        }
+    }
+
+    /// Returns the registers stacked by the hardware, if the crash was caused by a HardFault
+    pub fn frame(&self) -> Option<&ExceptionFrame> {
+        if self.kind() == CrashKind::HardFault {
+            Some(&self.frame)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the contents of the fault status registers, if the crash was caused by a HardFault
+    pub fn fault_info(&self) -> Option<&FaultInfo> {
+        if self.kind() == CrashKind::HardFault {
+            Some(&self.fault)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address of the first word returned by `stack`
+    ///
+    /// For a HardFault this is the address of the stacked exception frame; for a panic it's an
+    /// address within the stack frame of `save_panic`.
+    pub fn stack_address(&self) -> u32 {
+        self.stack_address
+    }
+
+    /// Returns the words found at the top of the stack when the crash happened
+    ///
+    /// At most `STACK_WORDS` words are saved. The snapshot is empty if the stack pointer was
+    /// outside of the stack and of the statically allocated RAM.
+    pub fn stack(&self) -> &[u32] {
+        &self.stack[..cmp::min(self.stack_len as usize, STACK_WORDS)]
+    }
+
+    /// Returns the file, line and column where the panic happened, if the crash was caused by a
+    /// panic
+    ///
+    /// The file name is truncated if it's longer than 64 bytes.
+    pub fn panic_location(&self) -> Option<(&str, u32, u32)> {
+        if self.kind() == CrashKind::Panic && self.file_len != 0 {
+            let file = bytes_to_str(&self.file, self.file_len)?;
+            Some((file, self.line, self.column))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the panic message, if the crash was caused by a panic
+    ///
+    /// The message is truncated if it's longer than 128 bytes.
+    pub fn panic_message(&self) -> Option<&str> {
+        if self.kind() == CrashKind::Panic {
+            bytes_to_str(&self.message, self.message_len)
+        } else {
+            None
+        }
+    }
+
+    /// Saves a snapshot of the stack starting at `sp`
+    fn snapshot_stack(&mut self, sp: u32) {
+        self.stack_address = sp;
+
+        // only read memory known to be RAM, otherwise we could trigger another fault
+        if let Some((_, end)) = ram_region(sp) {
+            let len = cmp::min((end - sp) as usize / 4, STACK_WORDS);
+            for i in 0..len {
+                self.stack[i] = unsafe { ptr::read_volatile((sp as *const u32).add(i)) };
+            }
+            self.stack_len = len as u32;
+        }
+    }
+}
+
+impl fmt::Debug for CrashRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut s = f.debug_struct("CrashRecord");
+        s.field("kind", &self.kind());
+        match self.kind() {
+            CrashKind::HardFault => {
+                s.field("frame", &self.frame).field("fault", &self.fault);
+            }
+            CrashKind::Panic => {
+                s.field("location", &self.panic_location())
+                    .field("message", &self.panic_message());
+            }
+        }
+        s.field("stack_address", &self.stack_address)
+            .field("stack", &self.stack())
+            .finish()
+    }
+}
+
+/// Returns the record of the last crash, if any, and clears it
+///
+/// This returns `None` if no crash has been recorded since the last power-on reset.
+pub fn take_last() -> Option<CrashRecord> {
+    unsafe { (*crash()).take() }
+        .filter(|record| record.kind == KIND_HARD_FAULT || record.kind == KIND_PANIC)
+}
+
+/// Records a crash caused by a HardFault
+///
+/// This is meant to be called from a `HardFault` handler. It overwrites any previous record.
+pub fn save_hard_fault(ef: &ExceptionFrame, info: &FaultInfo) {
+    unsafe {
+        (*crash()).set_with(|record| {
+            record.kind = KIND_HARD_FAULT;
+            record.frame = *ef;
+            record.fault = *info;
+            record.snapshot_stack(ef as *const ExceptionFrame as u32);
+        });
+    }
+}
+
+/// Records a crash caused by a panic
+///
+/// This is meant to be called from the panic handler. It overwrites any previous record.
+pub fn save_panic(info: &PanicInfo) {
+    // the address of a local variable is a good enough approximation of the stack pointer
+    let marker = 0u32;
+    let sp = &marker as *const u32 as u32;
+
+    unsafe {
+        (*crash()).set_with(|record| {
+            record.kind = KIND_PANIC;
+            if let Some(location) = info.location() {
+                record.file_len = copy_str(&mut record.file, location.file()) as u32;
+                record.line = location.line();
+                record.column = location.column();
+            }
+
+            let mut message = Truncate {
+                buf: &mut record.message,
+                len: 0,
+            };
+            let _ = write!(message, "{}", info);
+            record.message_len = message.len as u32;
+
+            record.snapshot_stack(sp);
+        });
+    }
+}
+
+/// Requests a system reset
+///
+/// Pending memory writes, like the ones made by `save_hard_fault` and `save_panic`, complete
+/// before the reset is requested.
+pub fn reset() -> ! {
+    atomic::fence(Ordering::SeqCst);
+    unsafe {
+        let aircr = ptr::read_volatile(SCB_AIRCR);
+        ptr::write_volatile(
+            SCB_AIRCR,
+            AIRCR_VECTKEY | (aircr & AIRCR_PRIGROUP_MASK) | AIRCR_SYSRESETREQ,
+        );
+    }
+
+    loop {
+        // wait for the reset to happen
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+}
+
+/// Copies as much of `s` as fits into `buf` without splitting a character and returns the number of
+/// bytes copied
+fn copy_str(buf: &mut [u8], s: &str) -> usize {
+    let mut len = cmp::min(buf.len(), s.len());
+    while !s.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    buf[..len].copy_from_slice(&s.as_bytes()[..len]);
+    len
+}
+
+fn bytes_to_str(buf: &[u8], len: u32) -> Option<&str> {
+    str::from_utf8(&buf[..cmp::min(len as usize, buf.len())]).ok()
+}
+
+/// `fmt::Write` implementation that drops whatever doesn't fit in `buf`
+struct Truncate<'a> {
+    buf: &'a mut [u8],
+    len: usize,
+}
+
+impl<'a> Write for Truncate<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = copy_str(&mut self.buf[self.len..], s);
+        self.len += len;
+
+        if len == s.len() {
+            Ok(())
+        } else {
+            Err(fmt::Error)
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Exception handling tables
+//!
+//! When the `exidx` feature is enabled the linker script keeps the exception handling tables
+//! described by the [Exception Handling ABI for the Arm Architecture][ehabi] (EHABI) in FLASH,
+//! instead of discarding them. These tables describe how to unwind the stack frame of each function
+//! and can be used by an unwinder to produce accurate backtraces, or to implement
+//! `panic = "unwind"`.
+//!
+//! [ehabi]: https://github.com/ARM-software/abi-aa/blob/main/ehabi32/ehabi32.rst
+//!
+//! The index table, `.ARM.exidx`, is delimited by the `__exidx_start` and `__exidx_end` linker
+//! symbols and is available through [`entries`](fn.entries.html). The exception handling table,
+//! `.ARM.extab`, which holds the unwinding instructions that don't fit in an index entry, is
+//! delimited by the `__extab_start` and `__extab_end` symbols.
+//!
+//! Note that, by default, the compiler only emits the unwinding information required by the panic
+//! strategy. When using `panic = "abort"` the program must be compiled with `-C
+//! force-unwind-tables=yes` to get useful tables.
+
+use core::slice;
+
+/// An entry of the exception index table (`.ARM.exidx`)
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct IndexEntry {
+    function: u32,
+    data: u32,
+}
+
+/// Value of the second word of an entry for a function that can't be unwound
+const EXIDX_CANTUNWIND: u32 = 1;
+
+impl IndexEntry {
+    /// Returns the address of the first instruction of the function this entry applies to
+    ///
+    /// The entry applies to all the addresses from this one up to, but not including, the
+    /// function address of the next entry.
+    pub fn function_address(&self) -> u32 {
+        prel31(&self.function)
+    }
+
+    /// Returns the raw contents of the second word of the entry
+    pub fn data(&self) -> u32 {
+        self.data
+    }
+
+    /// Returns `true` if the function can't be unwound
+    pub fn cant_unwind(&self) -> bool {
+        self.data == EXIDX_CANTUNWIND
+    }
+
+    /// Returns the unwinding instructions stored in the entry itself (compact model), if any
+    ///
+    /// These are the contents of the second word of the entry, which has its most significant bit
+    /// set.
+    pub fn inline_instructions(&self) -> Option<u32> {
+        if self.data & (1 << 31) != 0 {
+            Some(self.data)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address of the entry's exception handling table entry in `.ARM.extab`, if any
+    pub fn extab_address(&self) -> Option<u32> {
+        if self.cant_unwind() || self.inline_instructions().is_some() {
+            None
+        } else {
+            Some(prel31(&self.data))
+        }
+    }
+}
+
+/// Returns the entries of the exception index table, sorted by function address
+pub fn entries() -> &'static [IndexEntry] {
+    extern "C" {
+        static __exidx_start: IndexEntry;
+        static __exidx_end: IndexEntry;
+    }
+
+    unsafe {
+        let start = &__exidx_start as *const IndexEntry;
+        let end = &__exidx_end as *const IndexEntry;
+
+        slice::from_raw_parts(start, (end as usize - start as usize) / 8)
+    }
+}
+
+/// Returns the entry of the exception index table that applies to the instruction at `pc`, if any
+pub fn lookup(pc: u32) -> Option<&'static IndexEntry> {
+    let entries = entries();
+
+    // number of entries whose function starts at or below `pc`
+    let n = match entries.binary_search_by_key(&pc, |entry| entry.function_address()) {
+        Ok(i) => i + 1,
+        Err(i) => i,
+    };
+
+    if n == 0 {
+        None
+    } else {
+        Some(&entries[n - 1])
+    }
+}
+
+/// Returns the contents of the exception handling table (`.ARM.extab`)
+pub fn extab() -> &'static [u32] {
+    extern "C" {
+        static __extab_start: u32;
+        static __extab_end: u32;
+    }
+
+    unsafe {
+        let start = &__extab_start as *const u32;
+        let end = &__extab_end as *const u32;
+
+        slice::from_raw_parts(start, (end as usize - start as usize) / 4)
+    }
+}
+
+/// Resolves a 31-bit place-relative offset
+fn prel31(word: &u32) -> u32 {
+    let place = word as *const u32 as u32;
+    // sign extend bit 30
+    let offset = ((*word << 1) as i32 >> 1) as u32;
+
+    place.wrapping_add(offset)
+}
@@ -0,0 +1,323 @@
+//! Fault status decoding
+
+use core::fmt;
+use core::ptr;
+
+/// HardFault Status Register
+const SCB_HFSR: *const u32 = 0xE000_ED2C as *const u32;
+/// Configurable Fault Status Register
+const SCB_CFSR: *const u32 = 0xE000_ED28 as *const u32;
+/// MemManage Fault Address Register
+const SCB_MMFAR: *const u32 = 0xE000_ED34 as *const u32;
+/// BusFault Address Register
+const SCB_BFAR: *const u32 = 0xE000_ED38 as *const u32;
+/// Secure Fault Status Register
+const SAU_SFSR: *const u32 = 0xE000_EDE4 as *const u32;
+/// Secure Fault Address Register
+const SAU_SFAR: *const u32 = 0xE000_EDE8 as *const u32;
+
+// CFSR bits that tell whether the address registers are valid
+const MMARVALID: u32 = 1 << 7;
+const BFARVALID: u32 = 1 << 15;
+// SFSR bit that tells whether the SFAR is valid
+const SFARVALID: u32 = 1 << 6;
+
+/// Cause of a fault, as reported by the fault status registers
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FaultCause {
+    /// A bus fault occurred while reading the vector table (`HFSR.VECTTBL`)
+    VectorTableRead,
+    /// A configurable fault was escalated to a HardFault because its handler was disabled or
+    /// couldn't preempt the current execution priority (`HFSR.FORCED`)
+    Forced,
+    /// A debug event occurred while halting debug was disabled (`HFSR.DEBUGEVT`)
+    DebugEvent,
+
+    /// Instruction fetch from a location the MPU doesn't allow (`MMFSR.IACCVIOL`)
+    InstructionAccessViolation,
+    /// Data access to a location the MPU doesn't allow (`MMFSR.DACCVIOL`)
+    ///
+    /// The faulting address, if known, is returned by `FaultInfo::mmfar`.
+    DataAccessViolation,
+    /// MemManage fault while unstacking on exception return (`MMFSR.MUNSTKERR`)
+    MemManageUnstacking,
+    /// MemManage fault while stacking on exception entry (`MMFSR.MSTKERR`)
+    MemManageStacking,
+    /// MemManage fault during lazy floating-point state preservation (`MMFSR.MLSPERR`)
+    MemManageLazyFpStacking,
+
+    /// Bus error on instruction prefetch (`BFSR.IBUSERR`)
+    InstructionBusError,
+    /// Precise data bus error (`BFSR.PRECISERR`)
+    ///
+    /// The faulting address, if known, is returned by `FaultInfo::bfar`.
+    PreciseDataBusError,
+    /// Imprecise data bus error; the stacked PC doesn't point to the faulting instruction
+    /// (`BFSR.IMPRECISERR`)
+    ImpreciseDataBusError,
+    /// Bus fault while unstacking on exception return (`BFSR.UNSTKERR`)
+    BusFaultUnstacking,
+    /// Bus fault while stacking on exception entry (`BFSR.STKERR`)
+    BusFaultStacking,
+    /// Bus fault during lazy floating-point state preservation (`BFSR.LSPERR`)
+    BusFaultLazyFpStacking,
+
+    /// Execution of an undefined instruction (`UFSR.UNDEFINSTR`)
+    UndefinedInstruction,
+    /// Execution of an instruction with an invalid EPSR, e.g. a branch to an address without the
+    /// Thumb bit set (`UFSR.INVSTATE`)
+    InvalidState,
+    /// Exception return with an invalid EXC_RETURN value (`UFSR.INVPC`)
+    InvalidPcLoad,
+    /// Attempt to use a coprocessor that is disabled or not present, e.g. the FPU (`UFSR.NOCP`)
+    NoCoprocessor,
+    /// Stack pointer went below its limit register (`UFSR.STKOF`, ARMv8-M only)
+    StackOverflow,
+    /// Unaligned memory access (`UFSR.UNALIGNED`)
+    UnalignedAccess,
+    /// Integer division by zero (`UFSR.DIVBYZERO`)
+    DivideByZero,
+
+    /// Invalid Secure state entry point (`SFSR.INVEP`)
+    InvalidEntryPoint,
+    /// Invalid integrity signature in the exception stack frame (`SFSR.INVIS`)
+    InvalidIntegritySignature,
+    /// Invalid exception return (`SFSR.INVER`)
+    InvalidExceptionReturn,
+    /// Attribution unit violation (`SFSR.AUVIOL`)
+    ///
+    /// The faulting address, if known, is returned by `FaultInfo::sfar`.
+    AttributionUnitViolation,
+    /// Invalid transition from Secure to Non-secure state (`SFSR.INVTRAN`)
+    InvalidTransition,
+    /// Security violation during lazy floating-point state preservation (`SFSR.LSPERR`)
+    LazyStatePreservation,
+    /// Error during lazy floating-point state activation or deactivation (`SFSR.LSERR`)
+    LazyStateError,
+}
+
+/// Bits of the HFSR and the causes they report
+const HFSR_CAUSES: &[(u32, FaultCause)] = &[
+    (1 << 1, FaultCause::VectorTableRead),
+    (1 << 30, FaultCause::Forced),
+    (1 << 31, FaultCause::DebugEvent),
+];
+
+/// Bits of the CFSR and the causes they report
+const CFSR_CAUSES: &[(u32, FaultCause)] = &[
+    (1 << 0, FaultCause::InstructionAccessViolation),
+    (1 << 1, FaultCause::DataAccessViolation),
+    (1 << 3, FaultCause::MemManageUnstacking),
+    (1 << 4, FaultCause::MemManageStacking),
+    (1 << 5, FaultCause::MemManageLazyFpStacking),
+    (1 << 8, FaultCause::InstructionBusError),
+    (1 << 9, FaultCause::PreciseDataBusError),
+    (1 << 10, FaultCause::ImpreciseDataBusError),
+    (1 << 11, FaultCause::BusFaultUnstacking),
+    (1 << 12, FaultCause::BusFaultStacking),
+    (1 << 13, FaultCause::BusFaultLazyFpStacking),
+    (1 << 16, FaultCause::UndefinedInstruction),
+    (1 << 17, FaultCause::InvalidState),
+    (1 << 18, FaultCause::InvalidPcLoad),
+    (1 << 19, FaultCause::NoCoprocessor),
+    (1 << 20, FaultCause::StackOverflow),
+    (1 << 24, FaultCause::UnalignedAccess),
+    (1 << 25, FaultCause::DivideByZero),
+];
+
+/// Bits of the SFSR and the causes they report
+const SFSR_CAUSES: &[(u32, FaultCause)] = &[
+    (1 << 0, FaultCause::InvalidEntryPoint),
+    (1 << 1, FaultCause::InvalidIntegritySignature),
+    (1 << 2, FaultCause::InvalidExceptionReturn),
+    (1 << 3, FaultCause::AttributionUnitViolation),
+    (1 << 4, FaultCause::InvalidTransition),
+    (1 << 5, FaultCause::LazyStatePreservation),
+    (1 << 7, FaultCause::LazyStateError),
+];
+
+/// Snapshot of the fault status and address registers
+///
+/// ARMv6-M and ARMv8-M Baseline devices don't implement these registers, so on those targets all
+/// of them read as zero and no cause is ever reported. The Secure fault registers are only read on
+/// ARMv8-M Mainline; they read as zero from the Non-secure state.
+#[derive(Clone, Copy)]
+#[repr(C)]
+pub struct FaultInfo {
+    hfsr: u32,
+    cfsr: u32,
+    mmfar: u32,
+    bfar: u32,
+    sfsr: u32,
+    sfar: u32,
+}
+
+impl FaultInfo {
+    /// Reads the fault status and address registers of the current core
+    ///
+    /// This is meant to be called from a fault handler. Note that the MMFAR and BFAR registers
+    /// may be overwritten if another fault occurs while handling the current one.
+    #[inline]
+    pub fn read() -> Self {
+        if cfg!(any(armv6m, armv8m_base)) {
+            return FaultInfo {
+                hfsr: 0,
+                cfsr: 0,
+                mmfar: 0,
+                bfar: 0,
+                sfsr: 0,
+                sfar: 0,
+            };
+        }
+
+        unsafe {
+            let (sfsr, sfar) = if cfg!(armv8m_main) {
+                (ptr::read_volatile(SAU_SFSR), ptr::read_volatile(SAU_SFAR))
+            } else {
+                (0, 0)
+            };
+
+            FaultInfo {
+                hfsr: ptr::read_volatile(SCB_HFSR),
+                cfsr: ptr::read_volatile(SCB_CFSR),
+                mmfar: ptr::read_volatile(SCB_MMFAR),
+                bfar: ptr::read_volatile(SCB_BFAR),
+                sfsr,
+                sfar,
+            }
+        }
+    }
+
+    /// Returns the raw value of the HardFault Status Register
+    #[inline(always)]
+    pub fn hfsr(&self) -> u32 {
+        self.hfsr
+    }
+
+    /// Returns the raw value of the Configurable Fault Status Register
+    ///
+    /// This register is the concatenation of the UsageFault (UFSR), BusFault (BFSR) and MemManage
+    /// (MMFSR) status registers.
+    #[inline(always)]
+    pub fn cfsr(&self) -> u32 {
+        self.cfsr
+    }
+
+    /// Returns the raw value of the Secure Fault Status Register
+    #[inline(always)]
+    pub fn sfsr(&self) -> u32 {
+        self.sfsr
+    }
+
+    /// Returns the address that caused a `DataAccessViolation`, if it's known
+    #[inline]
+    pub fn mmfar(&self) -> Option<u32> {
+        if self.cfsr & MMARVALID != 0 {
+            Some(self.mmfar)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address that caused a `PreciseDataBusError`, if it's known
+    #[inline]
+    pub fn bfar(&self) -> Option<u32> {
+        if self.cfsr & BFARVALID != 0 {
+            Some(self.bfar)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the address that caused a Secure fault, if it's known
+    #[inline]
+    pub fn sfar(&self) -> Option<u32> {
+        if self.sfsr & SFARVALID != 0 {
+            Some(self.sfar)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if `cause` is reported by the fault status registers
+    pub fn has(&self, cause: FaultCause) -> bool {
+        self.causes().any(|c| c == cause)
+    }
+
+    /// Returns an iterator over all the fault causes reported by the fault status registers
+    ///
+    /// Several causes may be reported at once, e.g. `Forced` together with the configurable fault
+    /// that was escalated to a HardFault.
+    pub fn causes(&self) -> FaultCauses {
+        FaultCauses { info: *self, i: 0 }
+    }
+}
+
+impl fmt::Debug for FaultInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Hex(u32);
+        impl fmt::Debug for Hex {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "0x{:08x}", self.0)
+            }
+        }
+        struct List(FaultCauses);
+        impl fmt::Debug for List {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.debug_list().entries(self.0.clone()).finish()
+            }
+        }
+        f.debug_struct("FaultInfo")
+            .field("causes", &List(self.causes()))
+            .field("hfsr", &Hex(self.hfsr))
+            .field("cfsr", &Hex(self.cfsr))
+            .field("mmfar", &self.mmfar().map(Hex))
+            .field("bfar", &self.bfar().map(Hex))
+            .field("sfsr", &Hex(self.sfsr))
+            .field("sfar", &self.sfar().map(Hex))
+            .finish()
+    }
+}
+
+/// Iterator over the causes of a fault
+///
+/// This `struct` is created by [`FaultInfo::causes`](struct.FaultInfo.html#method.causes).
+#[derive(Clone)]
+pub struct FaultCauses {
+    info: FaultInfo,
+    i: usize,
+}
+
+impl Iterator for FaultCauses {
+    type Item = FaultCause;
+
+    fn next(&mut self) -> Option<FaultCause> {
+        loop {
+            let (reg, &(mask, cause)) = self.entry(self.i)?;
+            self.i += 1;
+
+            if reg & mask != 0 {
+                return Some(cause);
+            }
+        }
+    }
+}
+
+impl FaultCauses {
+    /// Returns the `i`-th entry of the concatenation of all the cause tables, along with the
+    /// value of the register it refers to
+    fn entry(&self, mut i: usize) -> Option<(u32, &'static (u32, FaultCause))> {
+        for &(reg, table) in &[
+            (self.info.hfsr, HFSR_CAUSES),
+            (self.info.cfsr, CFSR_CAUSES),
+            (self.info.sfsr, SFSR_CAUSES),
+        ] {
+            if i < table.len() {
+                return Some((reg, &table[i]));
+            }
+            i -= table.len();
+        }
+
+        None
+    }
+}
@@ -0,0 +1,51 @@
+//! Fixed-address configuration blocks
+
+/// Defines the contents of the block of FLASH reserved using the `_flash_config_start` and
+/// `_flash_config_end` linker symbols
+///
+/// `flash_config!(static NAME: Type = value;)` declares a `static` that's placed in the
+/// `.flash_config` section, which the linker puts at `_flash_config_start` (see
+/// [`memory.x`](index.html#_flash_config_start-and-_flash_config_end)). The `static` is kept even
+/// if the program never uses it. If the macro is used more than once the `static`s are placed one
+/// after the other, in link order; linking fails if they don't fit in the reserved block.
+///
+/// The block is in FLASH so the type must be `Copy`, which rules out types with interior
+/// mutability like atomics. The value is written as is, so its layout must match what the hardware
+/// expects: use byte arrays or `#[repr(C)]` types.
+///
+/// # Examples
+///
+/// ``` ignore
+/// use cortex_m_rt::flash_config;
+///
+/// // Flash Configuration Field of a Kinetis part, at 0x400: backdoor key, protection registers,
+/// // and FSEC / FOPT / FEPROT / FDPROT; the security byte keeps the part unsecured
+/// flash_config! {
+///     static FLASH_CONFIG: [u8; 16] = [
+///         0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+///         0xFF, 0xFF, 0xFF, 0xFF, 0xFE, 0xFF, 0xFF, 0xFF,
+///     ];
+/// }
+/// ```
+#[macro_export]
+macro_rules! flash_config {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $value:expr;) => {
+        $(#[$attr])*
+        // Only emit link_section when building for embedded targets, because some hosted
+        // platforms (used to check the build) cannot handle the long link section names.
+        #[cfg_attr(target_os = "none", link_section = ".flash_config")]
+        #[used]
+        $vis static $name: $ty = $value;
+
+        const _: () = {
+            #[allow(dead_code)]
+            fn check() {
+                $crate::assert_flash_config::<$ty>();
+            }
+        };
+    };
+}
+
+/// Only accepts the types that can be placed in FLASH
+#[doc(hidden)]
+pub fn assert_flash_config<T: Copy>() {}
@@ -0,0 +1,209 @@
+//! Exceptions and interrupts
+
+use core::slice;
+
+/// A core exception
+///
+/// The variants that exist depend on the architecture of the target: ARMv6-M doesn't have
+/// `MemoryManagement`, `BusFault`, `UsageFault` and `DebugMonitor`, and only ARMv8-M has
+/// `SecureFault`. `Reset` is not included because it can't be handled like the other exceptions.
+/// As the set of variants depends on the target, matching on this enum requires a wildcard arm.
+// `#[non_exhaustive]` requires Rust 1.40
+#[allow(clippy::manual_non_exhaustive)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exception {
+    /// Non-maskable interrupt
+    NonMaskableInt,
+
+    /// Hard fault; all the faults escalate to this exception when they can't be handled
+    HardFault,
+
+    /// Memory management fault: violation of the MPU rules
+    #[cfg(not(armv6m))]
+    MemoryManagement,
+
+    /// Bus fault: error on a memory access
+    #[cfg(not(armv6m))]
+    BusFault,
+
+    /// Usage fault: e.g. undefined instruction or unaligned access
+    #[cfg(not(armv6m))]
+    UsageFault,
+
+    /// Secure fault: violation of the TrustZone rules
+    #[cfg(armv8m)]
+    SecureFault,
+
+    /// Supervisor call, raised by the `svc` instruction
+    SVCall,
+
+    /// Debug monitor
+    #[cfg(not(armv6m))]
+    DebugMonitor,
+
+    /// Pendable request for system service
+    PendSV,
+
+    /// System tick timer
+    SysTick,
+
+    // makes the enum non-exhaustive; this variant is never constructed
+    #[doc(hidden)]
+    __Nonexhaustive,
+}
+
+/// All the exceptions of the target, in the order of the vector table
+const EXCEPTIONS: &[Exception] = &[
+    Exception::NonMaskableInt,
+    Exception::HardFault,
+    #[cfg(not(armv6m))]
+    Exception::MemoryManagement,
+    #[cfg(not(armv6m))]
+    Exception::BusFault,
+    #[cfg(not(armv6m))]
+    Exception::UsageFault,
+    #[cfg(armv8m)]
+    Exception::SecureFault,
+    Exception::SVCall,
+    #[cfg(not(armv6m))]
+    Exception::DebugMonitor,
+    Exception::PendSV,
+    Exception::SysTick,
+];
+
+/// Iterator over the exceptions of the target
+///
+/// Returned by [`Exception::all`](enum.Exception.html#method.all).
+pub struct Exceptions {
+    inner: slice::Iter<'static, Exception>,
+}
+
+impl Iterator for Exceptions {
+    type Item = Exception;
+
+    fn next(&mut self) -> Option<Exception> {
+        self.inner.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for Exceptions {}
+
+impl Exception {
+    /// Returns the IRQ number of the exception (see CMSIS), e.g. `-1` for `SysTick`
+    ///
+    /// This is the exception number minus 16, so it's always negative.
+    pub fn irqn(self) -> i16 {
+        match self {
+            Exception::NonMaskableInt => -14,
+            Exception::HardFault => -13,
+            #[cfg(not(armv6m))]
+            Exception::MemoryManagement => -12,
+            #[cfg(not(armv6m))]
+            Exception::BusFault => -11,
+            #[cfg(not(armv6m))]
+            Exception::UsageFault => -10,
+            #[cfg(armv8m)]
+            Exception::SecureFault => -9,
+            Exception::SVCall => -5,
+            #[cfg(not(armv6m))]
+            Exception::DebugMonitor => -4,
+            Exception::PendSV => -2,
+            Exception::SysTick => -1,
+            Exception::__Nonexhaustive => unreachable!(),
+        }
+    }
+
+    /// Returns the exception with IRQ number `irqn`
+    ///
+    /// Returns `None` if there's no such exception on the target.
+    pub fn from_irqn(irqn: i16) -> Option<Exception> {
+        Exception::all().find(|exception| exception.irqn() == irqn)
+    }
+
+    /// Returns an iterator over all the exceptions of the target, in the order of the vector table
+    pub fn all() -> Exceptions {
+        Exceptions {
+            inner: EXCEPTIONS.iter(),
+        }
+    }
+}
+
+/// A core exception or a device specific interrupt
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExceptionOrIrq {
+    /// A core exception
+    Exception(Exception),
+
+    /// The device specific interrupt with the given number
+    Interrupt(u16),
+}
+
+impl ExceptionOrIrq {
+    /// Returns the IRQ number (see CMSIS): negative for exceptions, the interrupt number for
+    /// interrupts
+    pub fn irqn(self) -> i16 {
+        match self {
+            ExceptionOrIrq::Exception(exception) => exception.irqn(),
+            ExceptionOrIrq::Interrupt(irqn) => irqn as i16,
+        }
+    }
+
+    /// Returns the exception or interrupt with IRQ number `irqn`
+    ///
+    /// Returns `None` if `irqn` is negative and there's no exception with that IRQ number on the
+    /// target.
+    pub fn from_irqn(irqn: i16) -> Option<ExceptionOrIrq> {
+        if irqn >= 0 {
+            Some(ExceptionOrIrq::Interrupt(irqn as u16))
+        } else {
+            Exception::from_irqn(irqn).map(ExceptionOrIrq::Exception)
+        }
+    }
+
+    /// Returns the exception or interrupt being serviced
+    ///
+    /// This reads the IPSR register. Returns `None` in thread mode, i.e. when not called from an
+    /// exception or interrupt handler.
+    #[inline]
+    pub fn active() -> Option<ExceptionOrIrq> {
+        extern "C" {
+            fn __cortex_m_rt_ipsr() -> u32;
+        }
+
+        match unsafe { __cortex_m_rt_ipsr() } & 0x1FF {
+            0 => None,
+            number => ExceptionOrIrq::from_irqn(number as i16 - 16),
+        }
+    }
+}
+
+impl From<Exception> for ExceptionOrIrq {
+    fn from(exception: Exception) -> Self {
+        ExceptionOrIrq::Exception(exception)
+    }
+}
+
+/// Converts the IRQ number of the exception or interrupt being serviced into the argument of the
+/// `DefaultHandler`
+#[doc(hidden)]
+pub trait DefaultHandlerArg: Sized {
+    fn from_irqn(irqn: i16) -> Option<Self>;
+}
+
+impl DefaultHandlerArg for i16 {
+    #[inline(always)]
+    fn from_irqn(irqn: i16) -> Option<Self> {
+        Some(irqn)
+    }
+}
+
+impl DefaultHandlerArg for ExceptionOrIrq {
+    #[inline(always)]
+    fn from_irqn(irqn: i16) -> Option<Self> {
+        ExceptionOrIrq::from_irqn(irqn)
+    }
+}
@@ -8,9 +8,10 @@
 //! This crates takes care of:
 //!
 //! - The memory layout of the program. In particular, it populates the vector table so the device
-//! can boot correctly, and properly dispatch exceptions and interrupts.
+//!   can boot correctly, and properly dispatch exceptions and interrupts.
 //!
-//! - Initializing `static` variables before the program entry point.
+//! - Initializing `static` variables before the program entry point, including those placed in
+//!   additional memory regions (see [Extra Sections](#initializing-extra-sections)).
 //!
 //! - Enabling the FPU before the program entry point if the target is `thumbv7em-none-eabihf`.
 //!
@@ -20,6 +21,7 @@
 //! - [`#[exception]`][attr-exception] to override an exception handler. If not overridden all
 //!   exception handlers default to an infinite loop.
 //! - [`#[pre_init]`][attr-pre_init] to run code *before* `static` variables are initialized
+//! - [`#[ramfunc]`][attr-ramfunc] to place a function in RAM
 //!
 //! This crate also implements a related attribute called `#[interrupt]`, which allows you
 //! to define interrupt handlers. However, since which interrupts are available depends on the
@@ -75,13 +77,88 @@
 //! _stack_start = ORIGIN(CCRAM) + LENGTH(CCRAM);
 //! ```
 //!
+//! ### `_stack_end`
+//!
+//! This optional symbol indicates the lowest address the stack is expected to grow down to. It's
+//! only used by the `paint-stack` feature to know which memory to paint. If omitted it defaults to
+//! the end of the heap, `_heap_size` bytes after [`heap_start`](fn.heap_start.html), i.e. the stack
+//! may use all the `RAM` left after `.bss`, `.data`, `.uninit` and the heap. `_heap_size` defaults
+//! to 0, so **a program that uses a heap must set `_heap_size` (or `_stack_end`)**; otherwise the
+//! heap is painted as part of the stack and the stack is reported as fully used as soon as the
+//! heap reaches the painted area. If `_stack_start` has been moved to a different memory region
+//! then `_stack_end` must be set as well:
+//!
+//! ```text
+//! /* 16 KiB of heap right after `.uninit`; the stack can use the rest of RAM */
+//! _heap_size = 0x4000;
+//!
+//! /* or, with the stack in a different memory region */
+//! _stack_start = ORIGIN(CCRAM) + LENGTH(CCRAM);
+//! _stack_end = ORIGIN(CCRAM);
+//! ```
+//!
+//! ### `_psp_stack_start` and `_psp_stack_size`
+//!
+//! These optional symbols are only used when the `process-stack` feature is enabled. They define
+//! the process stack, the stack `main` runs on. By default `_psp_stack_size` bytes (2 KiB unless
+//! set in `memory.x`) are reserved for it in `RAM`, right after `.uninit`, and `_psp_stack_start`
+//! is the end of that space. To place the process stack somewhere else, set `_psp_stack_start` and
+//! set `_psp_stack_size` to 0. Both must be 8-byte aligned.
+//!
+//! ```text
+//! /* 4 KiB of process stack */
+//! _psp_stack_size = 0x1000;
+//! ```
+//!
+//! ### `_vector_table_start`
+//!
+//! This optional symbol can be used to control where the vector table is placed. If omitted the
+//! vector table will be placed at the beginning of `FLASH`. This is useful when the device expects
+//! a boot header before the vector table, or when the program is started by a bootloader that
+//! lives at the beginning of `FLASH`.
+//!
+//! `VTOR` requires the vector table to be aligned to its size rounded up to the next power of two,
+//! and to at least 128 bytes, so e.g. a table with 64 interrupt vectors (80 vectors in total) must
+//! be 512-byte aligned. Placing the table at an address that doesn't meet this requirement results
+//! in a link error.
+//!
+//! ```text
+//! MEMORY
+//! {
+//!   /* .. */
+//! }
+//!
+//! /* The bootloader occupies the first 16 KiB of Flash */
+//! _vector_table_start = ORIGIN(FLASH) + 0x4000;
+//! ```
+//!
 //! ### `_stext`
 //!
 //! This optional symbol can be used to control where the `.text` section is placed. If omitted the
-//! `.text` section will be placed right after the vector table, which is placed at the beginning of
-//! `FLASH`. Some devices store settings like Flash configuration right after the vector table;
-//! for these devices one must place the `.text` section after this configuration section --
-//! `_stext` can be used for this purpose.
+//! `.text` section will be placed right after the vector table (see
+//! [`_vector_table_start`](#_vector_table_start)).
+//!
+//! ```text
+//! MEMORY
+//! {
+//!   /* .. */
+//! }
+//!
+//! /* Leave the first 4 KiB after the vector table unused */
+//! _stext = ORIGIN(FLASH) + 0x1400;
+//! ```
+//!
+//! ### `_flash_config_start` and `_flash_config_end`
+//!
+//! These optional symbols reserve a block of `FLASH` at a fixed address, from `_flash_config_start`
+//! up to, but not including, `_flash_config_end`. Some devices expect configuration data at a fixed
+//! address, e.g. the Flash Configuration Field of Kinetis parts or a header read by the boot ROM.
+//! The contents of the block are defined using the [`flash_config!`](macro.flash_config.html)
+//! macro; without it the block is left erased.
+//!
+//! `.text` and `.rodata` are placed before the block if they fit there, and after it otherwise, so
+//! the space between the vector table and the block isn't wasted. The (small) sections that follow
+//! them in `FLASH` are always placed after the block.
 //!
 //! ```text
 //! MEMORY
@@ -89,8 +166,9 @@
 //!   /* .. */
 //! }
 //!
-//! /* The device stores Flash configuration in 0x400-0x40C so we place .text after that */
-//! _stext = ORIGIN(FLASH) + 0x40C
+//! /* The device stores Flash configuration in 0x400-0x40F */
+//! _flash_config_start = ORIGIN(FLASH) + 0x400;
+//! _flash_config_end = ORIGIN(FLASH) + 0x410;
 //! ```
 //!
 //! # An example
@@ -158,6 +236,69 @@
 //! conjunction with crates generated using `svd2rust`. Those *device crates* will populate the
 //! missing part of the vector table when their `"rt"` feature is enabled.
 //!
+//! ## `paint-stack`
+//!
+//! If this feature is enabled then the `Reset` handler fills the whole stack, from `_stack_end` to
+//! `_stack_start`, with a known pattern before calling the program entry point. The functions in
+//! the [`stack`](stack/index.html) module can then be used to measure how much of the stack has
+//! been used at runtime.
+//!
+//! ## `exidx`
+//!
+//! By default the linker script discards the exception handling tables (the `.ARM.exidx` and
+//! `.ARM.extab` sections) because they are only used to unwind the stack. If this feature is
+//! enabled then they are kept in FLASH instead, delimited by the `__exidx_start` / `__exidx_end`
+//! and `__extab_start` / `__extab_end` symbols, and they can be accessed using the functions in the
+//! [`exidx`](exidx/index.html) module.
+//!
+//! ## `crash-record`
+//!
+//! If this feature is enabled then the default `HardFault` handler saves the state of the
+//! processor in the `.persist` section and resets the device, instead of looping forever. After the
+//! reset, the record can be retrieved using the functions in the [`crash`](crash/index.html)
+//! module.
+//!
+//! ## `syscalls`
+//!
+//! If this feature is enabled then `cortex-m-rt` provides the `SVCall` handler, which dispatches
+//! supervisor calls to the functions registered using the [`#[syscall(n)]`][attr-syscall]
+//! attribute. Supervisor calls are made using the [`svc_call!`](macro.svc_call.html) macro. As the
+//! runtime defines `SVCall`, the program must not define its own `SVCall` handler.
+//!
+//! ## `process-stack`
+//!
+//! By default `main` runs in privileged thread mode on the main stack, the same stack exception
+//! handlers use. If this feature is enabled then `Reset` sets up a separate process stack (see
+//! [`_psp_stack_start`](#_psp_stack_start-and-_psp_stack_size)) and switches thread mode to it
+//! before calling `main`, while exception handlers keep using the main stack, which starts at
+//! `_stack_start`. That way an overflow of the stack of `main` can't corrupt the stack of the
+//! exception handlers. Note that the `paint-stack` feature and the `stack` module only cover the
+//! main stack.
+//!
+//! ## `unprivileged`
+//!
+//! This feature enables the `process-stack` feature and, in addition, makes `main` run in
+//! unprivileged mode. Unprivileged code can't access most of the System Control Space (e.g. the
+//! NVIC or SysTick) nor execute privileged instructions like `cpsid`; it has to use supervisor
+//! calls (see the `syscalls` feature) to ask privileged code to do it.
+//!
+//! ## `ram-vector-table`
+//!
+//! If this feature is enabled then `Reset` copies the vector table into the `.vector_table_ram`
+//! section, which is reserved in `RAM` with the alignment VTOR requires, and points VTOR to that
+//! copy before calling `main`. Exception and interrupt handlers can then be installed at runtime
+//! using the functions in the [`vectors`](vectors/index.html) module. This feature requires a core
+//! that implements VTOR, so it's rejected when building for an ARMv6-M (`thumbv6m-`) target.
+//!
+//! ## `chain-load`
+//!
+//! If this feature is enabled then, before anything else, `Reset` points VTOR to the vector table
+//! of the program (see [`_vector_table_start`](#_vector_table_start)), switches to the main stack
+//! in privileged mode and reloads MSP with `_stack_start`, the first word of the vector table. This
+//! way an image started by a bootloader runs the same as when the device boots it directly, even if
+//! the bootloader jumps to `Reset` without setting up VTOR and MSP. This feature requires a core
+//! that implements VTOR, so it's rejected when building for an ARMv6-M (`thumbv6m-`) target.
+//!
 //! # Inspection
 //!
 //! This section covers how to inspect a binary that builds on top of `cortex-m-rt`.
@@ -194,38 +335,47 @@
 //! One will always find the following (unmangled) symbols in `cortex-m-rt` applications:
 //!
 //! - `Reset`. This is the reset handler. The microcontroller will execute this function upon
-//! booting. This function will call the user program entry point (cf. [`#[entry]`][attr-entry])
-//! using the `main` symbol so you will also find that symbol in your program.
+//!   booting. This function will call the user program entry point (cf. [`#[entry]`][attr-entry])
+//!   using the `main` symbol so you will also find that symbol in your program.
 //!
 //! - `DefaultHandler`. This is the default handler. If not overridden using `#[exception] fn
-//! DefaultHandler(..` this will be an infinite loop.
+//!   DefaultHandler(..` this will be an infinite loop. It also calls the handlers declared using
+//!   `#[interrupt(shared)]`.
 //!
 //! - `HardFaultTrampoline`. This is the real hard fault handler. This function is simply a
-//! trampoline that jumps into the user defined hard fault handler named `HardFault`. The
-//! trampoline is required to set up the pointer to the stacked exception frame.
+//!   trampoline that jumps into the user defined hard fault handler named `HardFault`. The
+//!   trampoline is required to set up the pointer to the stacked exception frame. When the handler
+//!   takes a `FullExceptionFrame`, `HardFaultFullTrampoline` is used instead.
+//!
+//! - `NonMaskableIntTrampoline`, `MemoryManagementTrampoline`, `BusFaultTrampoline`,
+//!   `UsageFaultTrampoline`, `SecureFaultTrampoline` and `SVCallTrampoline`. Like
+//!   `HardFaultTrampoline`, these set up the pointer to the stacked exception frame and then jump
+//!   into the corresponding handler. They are only used for the handlers that take the frame as
+//!   argument; the vector table points to the other handlers directly.
 //!
 //! - `HardFault`. This is the user defined hard fault handler. If not overridden using
-//! `#[exception] fn HardFault(..` it will default to an infinite loop.
+//!   `#[exception] fn HardFault(..` it will default to an infinite loop (or, with the
+//!   `crash-record` feature, to saving a crash record and resetting the device).
 //!
 //! - `__STACK_START`. This is the first entry in the `.vector_table` section. This symbol contains
-//! the initial value of the stack pointer; this is where the stack will be located -- the stack
-//! grows downwards towards smaller addresses.
+//!   the initial value of the stack pointer; this is where the stack will be located -- the stack
+//!   grows downwards towards smaller addresses.
 //!
 //! - `__RESET_VECTOR`. This is the reset vector, a pointer to the `Reset` function. This vector
-//! is located in the `.vector_table` section after `__STACK_START`.
+//!   is located in the `.vector_table` section after `__STACK_START`.
 //!
 //! - `__EXCEPTIONS`. This is the core exceptions portion of the vector table; it's an array of 14
-//! exception vectors, which includes exceptions like `HardFault` and `SysTick`. This array is
-//! located after `__RESET_VECTOR` in the `.vector_table` section.
+//!   exception vectors, which includes exceptions like `HardFault` and `SysTick`. This array is
+//!   located after `__RESET_VECTOR` in the `.vector_table` section.
 //!
 //! - `__INTERRUPTS`. This is the device specific interrupt portion of the vector table; its exact
-//! size depends on the target device but if the `"device"` feature has not been enabled it will
-//! have a size of 32 vectors (on ARMv6-M) or 240 vectors (on ARMv7-M). This array is located after
-//! `__EXCEPTIONS` in the `.vector_table` section.
+//!   size depends on the target device but if the `"device"` feature has not been enabled it will
+//!   have a size of 32 vectors (on ARMv6-M) or 240 vectors (on ARMv7-M). This array is located
+//!   after `__EXCEPTIONS` in the `.vector_table` section.
 //!
 //! - `__pre_init`. This is a function to be run before RAM is initialized. It defaults to an empty
-//! function. The function called can be changed by applying the [`#[pre_init]`][attr-pre_init]
-//! attribute to a function.
+//!   function. The function called can be changed by applying the [`#[pre_init]`][attr-pre_init]
+//!   attribute to a function.
 //!
 //! If you override any exception handler you'll find it as an unmangled symbol, e.g. `SysTick` or
 //! `SVCall`, in the output of `objdump`,
@@ -260,7 +410,7 @@
 //! variable named`__INTERRUPTS` (unmangled) that must be placed in the `.vector_table.interrupts`
 //! section of its object file.
 //!
-//! This `static` variable will be placed at `ORIGIN(FLASH) + 0x40`. This address corresponds to the
+//! This `static` variable will be placed at `_vector_table_start + 0x40`. This address corresponds to the
 //! spot where IRQ0 (IRQ number 0) is located.
 //!
 //! To conform to the Cortex-M ABI `__INTERRUPTS` must be an array of function pointers; some spots
@@ -374,8 +524,98 @@
 //! ```
 //!
 //! Be very careful with the `link_section` attribute because it's easy to misuse in ways that cause
-//! undefined behavior. At some point in the future we may add an attribute to safely place static
-//! variables in this section.
+//! undefined behavior. The `static mut` variables declared at the beginning of [`#[entry]`
+//! ][attr-entry], [`#[exception]`][attr-exception] and `#[interrupt]` functions can instead be
+//! placed in this section using the `#[uninit]` attribute. The attribute checks that the variable
+//! has type `MaybeUninit<T>` (or is an array of those) and that it's initialized with
+//! `MaybeUninit::uninit()`; access to it is then safe, just like for any other `static mut`
+//! declared in these functions.
+//!
+//! ```no_run,edition2018
+//! # #![no_main]
+//! # use cortex_m_rt::entry;
+//! use core::mem::MaybeUninit;
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     #[uninit]
+//!     static mut BUFFER: MaybeUninit<[u8; 1024]> = MaybeUninit::uninit();
+//!
+//!     // `BUFFER` has type `&'static mut MaybeUninit<[u8; 1024]>`
+//!     let buffer: &'static mut [u8; 1024] = unsafe {
+//!         BUFFER.as_mut_ptr().write([0; 1024]);
+//!         &mut *BUFFER.as_mut_ptr()
+//!     };
+//!
+//!     loop {
+//!         /* .. */
+//!     }
+//! }
+//! ```
+//!
+//! ## Persistent static variables
+//!
+//! Like `.uninit`, the `.persist` linker section is left untouched by the `Reset` handler, so its
+//! contents survive a warm reset. This section is placed at the start of the `RAM` region, so its
+//! address doesn't change when the program changes. [`Persistent`] values placed in it using the
+//! `#[persist]` attribute can tell whether they hold data written before the reset or garbage
+//! left in RAM after a power-on reset; this is useful for crash counters, reset reasons or to pass
+//! data from a bootloader to the application.
+//!
+//! [`Persistent`]: struct.Persistent.html
+//!
+//! ```no_run,edition2018
+//! # #![no_main]
+//! # use cortex_m_rt::entry;
+//! use cortex_m_rt::{Persistent, PersistentData};
+//!
+//! #[derive(Clone, Copy)]
+//! #[repr(C)]
+//! struct ResetInfo {
+//!     count: u32,
+//!     reason: u32,
+//! }
+//!
+//! // any bit pattern is a valid `ResetInfo`, and it has no padding
+//! unsafe impl PersistentData for ResetInfo {}
+//!
+//! #[entry]
+//! fn main() -> ! {
+//!     #[persist]
+//!     static mut RESET_INFO: Persistent<ResetInfo> = Persistent::uninit();
+//!
+//!     let count = match RESET_INFO.get() {
+//!         Some(info) => info.count + 1,
+//!         // power-on reset
+//!         None => 0,
+//!     };
+//!     RESET_INFO.set(ResetInfo { count, reason: 0 });
+//!
+//!     loop {
+//!         /* .. */
+//!     }
+//! }
+//! ```
+//!
+//! Some devices have RAM banks that stay powered in low-power standby modes. The `.persist`
+//! section can be moved to one of those from `memory.x`:
+//!
+//! ```text
+//! MEMORY
+//! {
+//!     /* .. */
+//!     BKPSRAM : ORIGIN = 0x40024000, LENGTH = 4K
+//! }
+//!
+//! SECTIONS
+//! {
+//!     .bkpsram (NOLOAD) : ALIGN(4)
+//!     {
+//!         *(.persist .persist.*);
+//!         . = ALIGN(4);
+//!     } > BKPSRAM
+//! } INSERT BEFORE .persist;
+//! ```
 //!
 //! ## Extra Sections
 //!
@@ -412,9 +652,53 @@
 //! static mut BUF: [u8; 1024] = [0u8; 1024];
 //! ```
 //!
+//! Note that `NOLOAD` sections like the one above are not initialized by the `Reset` handler, so
+//! the value written in the initializer is never actually stored in memory.
+//!
+//! ### Initializing extra sections
+//!
+//! The `Reset` handler initializes RAM by walking two tables that live in `FLASH`: a *copy table*,
+//! whose entries are (start, end, load address) triples, and a *zero table*, whose entries are
+//! (start, end) pairs. By default they only contain `.data` and `.bss`, respectively. Sections
+//! in other memory regions can be initialized the same way by registering them in these tables
+//! from `memory.x`:
+//!
+//! ```text
+//! SECTIONS
+//! {
+//!     .ccmram : ALIGN(4)
+//!     {
+//!         . = ALIGN(4);
+//!         __sccmram = .;
+//!         *(.ccmram .ccmram.*);
+//!         . = ALIGN(4);
+//!         __eccmram = .;
+//!     } > CCMRAM AT>FLASH
+//! } INSERT BEFORE .data;
+//!
+//! SECTIONS
+//! {
+//!     .copy_table.ccmram :
+//!     {
+//!         LONG(__sccmram);
+//!         LONG(__eccmram);
+//!         LONG(LOADADDR(.ccmram));
+//!     } > FLASH
+//! } INSERT AFTER .copy_table;
+//! ```
+//!
+//! Zero-initialized sections are registered in the same way, using two `LONG`s per entry and
+//! `INSERT AFTER .zero_table`. The start, end and load address of every registered section must
+//! be 4-byte aligned. `examples/ccmram.x` registers one section of each kind.
+//!
+//! Sections that are loaded in `FLASH` (`AT>FLASH`) must be inserted before `.data`, as above, so
+//! that they don't get in the way of `__edata` or of the `FLASH` sections placed after `.rodata`.
+//!
 //! [attr-entry]: attr.entry.html
 //! [attr-exception]: attr.exception.html
 //! [attr-pre_init]: attr.pre_init.html
+//! [attr-ramfunc]: attr.ramfunc.html
+//! [attr-syscall]: attr.syscall.html
 //!
 //! # Minimum Supported Rust Version (MSRV)
 //!
@@ -433,6 +717,44 @@ extern crate cortex_m_rt_macros as macros;
 use core::fmt;
 use core::sync::atomic::{self, Ordering};
 
+mod backtrace;
+pub mod boot;
+#[cfg(feature = "crash-record")]
+pub mod crash;
+#[cfg(feature = "exidx")]
+pub mod exidx;
+mod fault;
+mod flash_config;
+mod irq;
+mod persist;
+mod priority;
+mod shared;
+#[cfg(feature = "paint-stack")]
+pub mod stack;
+mod syscall;
+mod table;
+#[cfg(feature = "ram-vector-table")]
+pub mod vectors;
+
+pub use backtrace::Backtrace;
+pub use fault::{FaultCause, FaultCauses, FaultInfo};
+#[doc(hidden)]
+pub use flash_config::assert_flash_config;
+#[doc(hidden)]
+pub use irq::DefaultHandlerArg;
+pub use irq::{Exception, ExceptionOrIrq, Exceptions};
+pub use persist::{Persistent, PersistentData};
+#[doc(hidden)]
+pub use priority::Priority;
+#[doc(hidden)]
+pub use shared::{dispatch_shared, SharedHandler};
+#[doc(hidden)]
+pub use syscall::svc_call;
+#[cfg(feature = "syscalls")]
+#[doc(hidden)]
+pub use syscall::Syscall;
+pub use table::{vector_table, VectorEntries, VectorEntry, VectorTable};
+
 /// Attribute to declare an interrupt (AKA device-specific exception) handler
 ///
 /// **IMPORTANT**: If you are using Rust 1.30 this attribute must be used on reachable items (i.e.
@@ -460,7 +782,15 @@ use core::sync::atomic::{self, Ordering};
 /// }
 /// ```
 ///
-/// where the name of the function must be one of the device interrupts.
+/// where the name of the function must be one of the device interrupts, unless the names of the
+/// interrupts are given as arguments:
+///
+/// ``` ignore
+/// #[interrupt(USART1, USART2)]
+/// fn uart_irq() {
+///     // ..
+/// }
+/// ```
 ///
 /// # Usage
 ///
@@ -472,6 +802,22 @@ use core::sync::atomic::{self, Ordering};
 /// If the interrupt handler has not been overridden it will be dispatched by the default exception
 /// handler (`DefaultHandler`).
 ///
+/// `#[interrupt(Name1, Name2, ..)] fn name(..` overrides the handlers of all the listed interrupts
+/// with the same function, which can then have any name. Such a handler can't declare `static mut`
+/// variables: the interrupts may preempt each other, so they can't safely share them.
+///
+/// `#[interrupt(priority = N)]` also sets the priority of the interrupt to `N` before `main` is
+/// called, and `#[interrupt(priority = N, enable)]` additionally enables (unmasks) the interrupt in
+/// the NVIC. See [`#[exception]`](attr.exception.html) for how priorities are numbered.
+///
+/// `#[interrupt(shared)] fn Name(..` doesn't override the handler of the interrupt; it adds the
+/// function to the handlers shared by that interrupt instead. Any number of crates, or modules, can
+/// declare shared handlers for the same interrupt, and all of them are called, in link order, when
+/// the interrupt fires. The shared handlers are called by `DefaultHandler`, both the default one
+/// and one declared using `#[exception]`, so they're never called if the interrupt also has a
+/// handler that isn't shared, or if `DefaultHandler` is defined by other means. Interrupts without
+/// shared handlers don't go through `DefaultHandler`, so they're not slowed down in any way.
+///
 /// # Properties
 ///
 /// Interrupts handlers can only be called by the hardware. Other parts of the program can't refer
@@ -527,6 +873,10 @@ pub use macros::interrupt;
 /// this reason a variable like `static mut FOO: u32` will become `let FOO: &'static mut u32;`. Note
 /// that `&'static mut` references have move semantics.
 ///
+/// `static mut` variables with type `MaybeUninit<T>` can be marked with the `#[uninit]` attribute
+/// to place them in the `.uninit` section, so they are not initialized by the reset handler (see
+/// [Uninitialized static variables](index.html#uninitialized-static-variables)).
+///
 /// # Examples
 ///
 /// - Simple entry point
@@ -603,8 +953,26 @@ pub use macros::entry;
 /// # Usage
 ///
 /// `#[exception] unsafe fn HardFault(..` sets the hard fault handler. The handler must have
-/// signature `unsafe fn(&ExceptionFrame) -> !`. This handler is not allowed to return as that can
-/// cause undefined behavior.
+/// signature `unsafe fn(&ExceptionFrame) -> !`. The handler can also take a second argument, in
+/// which case it must have signature `unsafe fn(&ExceptionFrame, &FaultInfo) -> !`; the
+/// [`FaultInfo`](struct.FaultInfo.html) argument holds the contents of the fault status registers,
+/// read on entry to the handler, and decodes them into a list of
+/// [`FaultCause`](enum.FaultCause.html)s.
+///
+/// The first argument can also be a `&FullExceptionFrame` instead of an `&ExceptionFrame`. The hard
+/// fault trampoline then also saves the callee-saved registers (`r4`-`r11`), the `EXC_RETURN`
+/// value, the stack pointers and the `CONTROL` register, which are otherwise lost by the time the
+/// handler runs (see [`FullExceptionFrame`](struct.FullExceptionFrame.html)). The type is detected
+/// by its name, so it must be written `FullExceptionFrame` (possibly with a path), not through a
+/// type alias. Handlers that take an `&ExceptionFrame` don't pay for saving these registers.
+///
+/// A `HardFault` handler that must recover from the fault, e.g. to probe whether some memory
+/// exists, can instead have signature `unsafe fn(&mut ExceptionFrame)` (or `unsafe fn(&mut
+/// ExceptionFrame, &FaultInfo)`). Such a handler returns from the exception, and the code that
+/// faulted resumes with the registers found in the frame. It's the handler's responsibility to
+/// make that safe, usually by moving the stacked `pc` past the faulting instruction; returning with
+/// the frame unchanged re-executes the instruction, which will most likely fault again. Note that
+/// the fault status registers are sticky, so the handler should also clear the bits it handled.
 ///
 /// `#[exception] unsafe fn DefaultHandler(..` sets the *default* handler. All exceptions which have
 /// not been assigned a handler will be serviced by this handler. This handler must have signature
@@ -612,11 +980,50 @@ pub use macros::entry;
 /// number when the handler is servicing a core exception; `irqn` will be a positive number when the
 /// handler is servicing a device specific exception (interrupt).
 ///
+/// The `DefaultHandler` can instead have signature `unsafe fn(irq: ExceptionOrIrq) [-> !]`, in which
+/// case it receives the [`ExceptionOrIrq`](enum.ExceptionOrIrq.html) being serviced rather than its
+/// raw IRQ number.
+///
+/// Interrupts with handlers declared using `#[interrupt(shared)]` are not passed to this handler;
+/// their shared handlers are called instead (see [`#[interrupt]`](attr.interrupt.html)).
+///
 /// `#[exception] fn Name(..` overrides the default handler for the exception with the given `Name`.
 /// These handlers must have signature `[unsafe] fn() [-> !]`. When overriding these other exception
 /// it's possible to add state to them by declaring `static mut` variables at the beginning of the
 /// body of the function. These variables will be safe to access from the function body.
 ///
+/// The name of the exception can also be given as argument, in which case the function can have any
+/// name: `#[exception(SysTick)] fn tick(..` overrides the `SysTick` handler. This applies to
+/// `DefaultHandler` and `HardFault` too.
+///
+/// The `NonMaskableInt`, `MemoryManagement`, `BusFault`, `UsageFault` and `SecureFault` handlers can
+/// also have signature `[unsafe] fn(&ExceptionFrame) [-> !]` or `[unsafe] fn(&mut ExceptionFrame)
+/// [-> !]`, in which case they receive the registers stacked on exception entry. Changes made
+/// through a `&mut ExceptionFrame` take effect when the handler returns; this can be used, for
+/// example, to skip the faulting instruction.
+///
+/// The `SVCall` handler can also have signature `[unsafe] fn(u8, &ExceptionFrame) [-> !]` or
+/// `[unsafe] fn(u8, &mut ExceptionFrame) [-> !]`. The first argument is then the immediate operand
+/// of the `svc` instruction that caused the exception, and the frame holds the registers of the
+/// caller, which can be used to pass arguments in `r0`-`r3` and return values in `r0`.
+///
+/// ## Priorities
+///
+/// `#[exception(priority = N)]` sets the priority of the exception to `N` before `main` is called,
+/// so priorities can be declared next to the handlers instead of being set by hand at the beginning
+/// of `main`. Only the `MemoryManagement`, `BusFault`, `UsageFault`, `SecureFault`, `SVCall`,
+/// `DebugMonitor`, `PendSV` and `SysTick` exceptions have a configurable priority.
+///
+/// `N` is the priority *level*, where 0 is the highest priority, and must be smaller than `1 <<
+/// NVIC_PRIO_BITS`, where `NVIC_PRIO_BITS` is the number of priority bits implemented by the device
+/// (svd2rust device crates export it as the `NVIC_PRIO_BITS` constant). The value written to the
+/// priority register is `N << (8 - NVIC_PRIO_BITS)`. The linker rejects priorities that don't fit
+/// in the `__nvic_prio_bits` symbol, which the device crate (in `device.x`) or the user (in
+/// `memory.x`) can set to `NVIC_PRIO_BITS`, e.g. `__nvic_prio_bits = 4;`. It defaults to 2 on
+/// ARMv6-M and to 8, which accepts every priority, on the other targets. In addition, the number of
+/// implemented bits is read from the hardware before the priorities are set, and a priority that
+/// doesn't fit panics.
+///
 /// # Properties
 ///
 /// Exception handlers can only be called by the hardware. Other parts of the program can't refer to
@@ -660,6 +1067,83 @@ pub use macros::entry;
 /// # fn main() {}
 /// ```
 ///
+/// - Setting a default handler that receives the exception or interrupt being serviced
+///
+/// ```
+/// use cortex_m_rt::{exception, Exception, ExceptionOrIrq};
+///
+/// #[exception]
+/// unsafe fn DefaultHandler(irq: ExceptionOrIrq) {
+///     match irq {
+///         ExceptionOrIrq::Exception(Exception::SysTick) => println!("unhandled SysTick"),
+///         ExceptionOrIrq::Exception(exception) => println!("unhandled {:?}", exception),
+///         ExceptionOrIrq::Interrupt(n) => println!("unhandled interrupt {}", n),
+///     }
+/// }
+///
+/// # fn main() {}
+/// ```
+///
+/// - Reporting where a bus fault happened
+///
+/// ```
+/// use cortex_m_rt::{exception, ExceptionFrame};
+///
+/// #[exception]
+/// fn BusFault(ef: &ExceptionFrame) -> ! {
+///     println!("bus fault at {:#010x}", ef.pc());
+///
+///     loop {}
+/// }
+///
+/// # fn main() {}
+/// ```
+///
+/// - Skipping a faulting load instruction
+///
+/// ```
+/// use cortex_m_rt::{exception, ExceptionFrame};
+///
+/// #[exception]
+/// unsafe fn HardFault(ef: &mut ExceptionFrame) {
+///     // assumes the faulting instruction is a 16-bit one
+///     let pc = ef.pc();
+///     ef.set_pc(pc + 2);
+/// }
+///
+/// # fn main() {}
+/// ```
+///
+/// - Implementing supervisor calls
+///
+/// ```
+/// use cortex_m_rt::{exception, ExceptionFrame};
+///
+/// #[exception]
+/// fn SVCall(num: u8, frame: &mut ExceptionFrame) {
+///     match num {
+///         // add the first two arguments
+///         0 => unsafe { frame.set_r0(frame.r0().wrapping_add(frame.r1())) },
+///         _ => {}
+///     }
+/// }
+///
+/// # fn main() {}
+/// ```
+///
+/// - Setting the priority of the `PendSV` exception
+///
+/// ```
+/// use cortex_m_rt::exception;
+///
+/// #[exception(priority = 1)]
+/// fn PendSV() {
+///     // ..
+/// }
+///
+/// # fn main() {}
+/// ```
+///
 /// - Overriding the `SysTick` handler
 ///
 /// ```
@@ -677,6 +1161,25 @@ pub use macros::entry;
 ///
 /// # fn main() {}
 /// ```
+///
+/// - Reporting the cause of a hard fault
+///
+/// ```
+/// use cortex_m_rt::{exception, ExceptionFrame, FaultInfo};
+///
+/// #[exception]
+/// unsafe fn HardFault(ef: &ExceptionFrame, info: &FaultInfo) -> ! {
+///     println!("{:#?}", ef);
+///
+///     for cause in info.causes() {
+///         println!("{:?}", cause);
+///     }
+///
+///     loop {}
+/// }
+///
+/// # fn main() {}
+/// ```
 pub use macros::exception;
 
 /// Attribute to mark which function will be called at the beginning of the reset handler.
@@ -716,6 +1219,69 @@ pub use macros::exception;
 /// [rfc1414]: https://github.com/rust-lang/rfcs/blob/master/text/1414-rvalue_static_promotion.md
 pub use macros::pre_init;
 
+/// Attribute to register a function as the handler of a supervisor call
+///
+/// **NOTE**: This attribute is only available when the `syscalls` feature is enabled.
+///
+/// `#[syscall(n)]` makes the runtime provided `SVCall` handler call the function whenever `svc #n`
+/// is executed, e.g. by [`svc_call!(n, ..)`](macro.svc_call.html). `n` must be in the range
+/// `0..=255` and a number can only be registered once; registering it twice results in a link
+/// error.
+///
+/// The function must have signature `fn([u32, ..]) [-> u32]` and take at most four arguments,
+/// which are the values of registers `r0`-`r3` at the time of the call. The value it returns, if
+/// any, is returned to the caller in `r0`. The function runs in handler mode, at the priority of
+/// `SVCall`. It can't be an `unsafe fn`: any code can make the call using the safe `svc_call!`
+/// macro.
+///
+/// # Examples
+///
+/// ``` ignore
+/// use cortex_m_rt::syscall;
+///
+/// #[syscall(1)]
+/// fn add(a: u32, b: u32) -> u32 {
+///     a.wrapping_add(b)
+/// }
+/// ```
+#[cfg(feature = "syscalls")]
+pub use macros::syscall;
+
+/// Attribute to place a function in RAM
+///
+/// The function is placed in the `.ramfunc` section, which is stored in FLASH and copied to RAM
+/// by the reset handler, together with `.data`, before `main` is called. Running from RAM avoids
+/// the FLASH wait states and lets the function run while the FLASH is being erased or programmed.
+///
+/// The function is never inlined into its callers. Calls between FLASH and RAM go through
+/// long-branch veneers inserted by the linker; the veneers used by the functions in RAM are placed
+/// in `.ramfunc` too. Note that the code a `#[ramfunc]` function calls, including the `core`
+/// functions that the compiler may call implicitly (e.g. `memcpy`), stays in FLASH unless it's
+/// marked with `#[ramfunc]` as well.
+///
+/// `#[ramfunc]` can be combined with `#[exception]` and `#[interrupt]`, in which case it must be
+/// placed *below* those attributes so that both the handler and the trampoline generated for it
+/// end up in RAM. It can't be used on the `HardFault` handler or on the `#[pre_init]` function,
+/// which runs before RAM is initialized.
+///
+/// # Examples
+///
+/// ``` ignore
+/// use cortex_m_rt::{exception, ramfunc};
+///
+/// #[ramfunc]
+/// fn checksum(data: &[u8]) -> u8 {
+///     data.iter().fold(0, |sum, byte| sum.wrapping_add(*byte))
+/// }
+///
+/// #[exception]
+/// #[ramfunc]
+/// fn SysTick() {
+///     // ..
+/// }
+/// ```
+pub use macros::ramfunc;
+
 // We export this static with an informative name so that if an application attempts to link
 // two copies of cortex-m-rt together, linking will fail. We also declare a links key in
 // Cargo.toml which is the more modern way to solve the same problem, but we have to keep
@@ -787,6 +1353,21 @@ impl ExceptionFrame {
         self.xpsr
     }
 
+    /// Returns an iterator over the call stack of the preempted code.
+    ///
+    /// The iterator yields the address of the instruction that was executing when the exception
+    /// was taken and then the addresses of the call instructions that are likely to have led to it.
+    /// See [`Backtrace`](struct.Backtrace.html) for how these are found and the limitations of the
+    /// approach.
+    ///
+    /// The stack is scanned starting right above `self`, so this method must be called on the
+    /// exception frame as stacked by the hardware (e.g. the one passed to the `HardFault` handler),
+    /// not on a copy of it. The returned iterator holds its own copy of the frame and doesn't
+    /// borrow `self`.
+    pub fn backtrace(&self) -> Backtrace {
+        Backtrace::new(self)
+    }
+
     /// Sets the stacked value of (general purpose) register 0.
     ///
     /// # Safety
@@ -897,9 +1478,185 @@ impl fmt::Debug for ExceptionFrame {
     }
 }
 
+/// Registers saved during a HardFault when the `HardFault` handler takes a `FullExceptionFrame`.
+///
+/// On top of the registers stacked by the hardware, which are available through
+/// [`frame`](#method.frame), this includes the callee-saved registers and the stack pointers and
+/// special registers at the time of the exception.
+///
+/// The struct lives on the stack of the `HardFault` handler and points into the hardware-stacked
+/// frame, so it can't be copied out of the handler.
+#[repr(C)]
+pub struct FullExceptionFrame {
+    r4: u32,
+    r5: u32,
+    r6: u32,
+    r7: u32,
+    r8: u32,
+    r9: u32,
+    r10: u32,
+    r11: u32,
+    exc_return: u32,
+    msp: u32,
+    psp: u32,
+    control: u32,
+    frame: *const ExceptionFrame,
+    _padding: u32,
+}
+
+impl FullExceptionFrame {
+    /// Returns the registers stacked by the hardware.
+    #[inline(always)]
+    pub fn frame(&self) -> &ExceptionFrame {
+        unsafe { &*self.frame }
+    }
+
+    /// Returns a mutable reference to the registers stacked by the hardware.
+    ///
+    /// Changes made through this reference take effect when a recoverable `HardFault` handler
+    /// returns. Note that the callee-saved registers in this struct are a copy; they are not
+    /// restored on return.
+    #[inline(always)]
+    pub fn frame_mut(&mut self) -> &mut ExceptionFrame {
+        unsafe { &mut *(self.frame as *mut ExceptionFrame) }
+    }
+
+    /// Returns the value of (general purpose) register 4.
+    #[inline(always)]
+    pub fn r4(&self) -> u32 {
+        self.r4
+    }
+
+    /// Returns the value of (general purpose) register 5.
+    #[inline(always)]
+    pub fn r5(&self) -> u32 {
+        self.r5
+    }
+
+    /// Returns the value of (general purpose) register 6.
+    #[inline(always)]
+    pub fn r6(&self) -> u32 {
+        self.r6
+    }
+
+    /// Returns the value of (general purpose) register 7.
+    #[inline(always)]
+    pub fn r7(&self) -> u32 {
+        self.r7
+    }
+
+    /// Returns the value of (general purpose) register 8.
+    #[inline(always)]
+    pub fn r8(&self) -> u32 {
+        self.r8
+    }
+
+    /// Returns the value of (general purpose) register 9.
+    #[inline(always)]
+    pub fn r9(&self) -> u32 {
+        self.r9
+    }
+
+    /// Returns the value of (general purpose) register 10.
+    #[inline(always)]
+    pub fn r10(&self) -> u32 {
+        self.r10
+    }
+
+    /// Returns the value of (general purpose) register 11.
+    #[inline(always)]
+    pub fn r11(&self) -> u32 {
+        self.r11
+    }
+
+    /// Returns the `EXC_RETURN` value the Link Register held on exception entry.
+    #[inline(always)]
+    pub fn exc_return(&self) -> u32 {
+        self.exc_return
+    }
+
+    /// Returns the value of the Main Stack Pointer on exception entry.
+    #[inline(always)]
+    pub fn msp(&self) -> u32 {
+        self.msp
+    }
+
+    /// Returns the value of the Process Stack Pointer on exception entry.
+    #[inline(always)]
+    pub fn psp(&self) -> u32 {
+        self.psp
+    }
+
+    /// Returns the value of the CONTROL register on exception entry.
+    #[inline(always)]
+    pub fn control(&self) -> u32 {
+        self.control
+    }
+
+    /// Returns the value the stack pointer had before the exception was taken.
+    ///
+    /// This is computed from the location of the hardware-stacked frame, taking into account the
+    /// floating-point context (if `EXC_RETURN` says it was stacked) and the padding the hardware
+    /// may have inserted to align the stack.
+    pub fn sp(&self) -> u32 {
+        // bit 4 of EXC_RETURN is cleared when the extended (FP) frame was stacked
+        let size = if self.exc_return & (1 << 4) == 0 {
+            0x68
+        } else {
+            0x20
+        };
+        // bit 9 of the stacked xPSR is set when a padding word was inserted
+        let padding = if self.frame().xpsr & (1 << 9) != 0 {
+            4
+        } else {
+            0
+        };
+
+        self.frame as u32 + size + padding
+    }
+}
+
+impl fmt::Debug for FullExceptionFrame {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        struct Hex(u32);
+        impl fmt::Debug for Hex {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "0x{:08x}", self.0)
+            }
+        }
+        f.debug_struct("FullExceptionFrame")
+            .field("frame", self.frame())
+            .field("r4", &Hex(self.r4))
+            .field("r5", &Hex(self.r5))
+            .field("r6", &Hex(self.r6))
+            .field("r7", &Hex(self.r7))
+            .field("r8", &Hex(self.r8))
+            .field("r9", &Hex(self.r9))
+            .field("r10", &Hex(self.r10))
+            .field("r11", &Hex(self.r11))
+            .field("sp", &Hex(self.sp()))
+            .field("exc_return", &Hex(self.exc_return))
+            .field("msp", &Hex(self.msp))
+            .field("psp", &Hex(self.psp))
+            .field("control", &Hex(self.control))
+            .finish()
+    }
+}
+
+/// Returns the immediate operand of the `svc` instruction that caused the `SVCall` exception
+///
+/// The stacked `pc` points to the instruction that follows the 16-bit `svc` instruction, whose low
+/// byte is the immediate.
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn svc_number(frame: &ExceptionFrame) -> u8 {
+    core::ptr::read_volatile(frame.pc().wrapping_sub(2) as *const u8)
+}
+
 /// Returns a pointer to the start of the heap
 ///
-/// The returned pointer is guaranteed to be 4-byte aligned.
+/// The returned pointer is guaranteed to be 4-byte aligned. Programs that use a heap should set
+/// the `_heap_size` linker symbol to its size (see [`_stack_end`](index.html#_stack_end)).
 #[inline]
 pub fn heap_start() -> *mut u32 {
     extern "C" {
@@ -915,6 +1672,7 @@ pub fn heap_start() -> *mut u32 {
 #[no_mangle]
 pub static __RESET_VECTOR: unsafe extern "C" fn() -> ! = Reset;
 
+#[cfg(not(feature = "crash-record"))]
 #[allow(unused_variables)]
 #[doc(hidden)]
 #[cfg_attr(cortex_m, link_section = ".HardFault.default")]
@@ -927,9 +1685,22 @@ pub unsafe extern "C" fn HardFault_(ef: &ExceptionFrame) -> ! {
     }
 }
 
+#[cfg(feature = "crash-record")]
+#[doc(hidden)]
+#[cfg_attr(cortex_m, link_section = ".HardFault.default")]
+#[no_mangle]
+pub unsafe extern "C" fn HardFault_(ef: &ExceptionFrame) -> ! {
+    crash::save_hard_fault(ef, &FaultInfo::read());
+    crash::reset()
+}
+
 #[doc(hidden)]
 #[no_mangle]
-pub unsafe extern "C" fn DefaultHandler_() -> ! {
+pub unsafe extern "C" fn DefaultHandler_() {
+    if dispatch_shared(shared::active_irqn()) {
+        return;
+    }
+
     loop {
         // add some side effect to prevent this from turning into a UDF instruction
         // see rust-lang/rust#28728 for details
@@ -941,33 +1712,11 @@ pub unsafe extern "C" fn DefaultHandler_() -> ! {
 #[no_mangle]
 pub unsafe extern "C" fn DefaultPreInit() {}
 
-/* Exceptions */
+/// Initialization that needs RAM to be initialized; `Reset` calls it right before `main`
 #[doc(hidden)]
-pub enum Exception {
-    NonMaskableInt,
-
-    // Not overridable
-    // HardFault,
-    #[cfg(not(armv6m))]
-    MemoryManagement,
-
-    #[cfg(not(armv6m))]
-    BusFault,
-
-    #[cfg(not(armv6m))]
-    UsageFault,
-
-    #[cfg(armv8m)]
-    SecureFault,
-
-    SVCall,
-
-    #[cfg(not(armv6m))]
-    DebugMonitor,
-
-    PendSV,
-
-    SysTick,
+#[no_mangle]
+pub unsafe extern "C" fn __cortex_m_rt_init() {
+    priority::apply();
 }
 
 #[doc(hidden)]
@@ -976,23 +1725,26 @@ pub use self::Exception as exception;
 extern "C" {
     fn Reset() -> !;
 
-    fn NonMaskableInt();
+    // The handler, or the trampoline that passes the exception frame to it; picked by the linker
+    // script, like `__hard_fault_trampoline`
+    fn __non_maskable_int_vector();
 
-    fn HardFaultTrampoline();
+    // `HardFaultTrampoline` or `HardFaultFullTrampoline`, picked by the linker script
+    fn __hard_fault_trampoline();
 
     #[cfg(not(armv6m))]
-    fn MemoryManagement();
+    fn __memory_management_vector();
 
     #[cfg(not(armv6m))]
-    fn BusFault();
+    fn __bus_fault_vector();
 
     #[cfg(not(armv6m))]
-    fn UsageFault();
+    fn __usage_fault_vector();
 
     #[cfg(armv8m)]
-    fn SecureFault();
+    fn __secure_fault_vector();
 
-    fn SVCall();
+    fn __sv_call_vector();
 
     #[cfg(not(armv6m))]
     fn DebugMonitor();
@@ -1014,35 +1766,37 @@ pub union Vector {
 pub static __EXCEPTIONS: [Vector; 14] = [
     // Exception 2: Non Maskable Interrupt.
     Vector {
-        handler: NonMaskableInt,
+        handler: __non_maskable_int_vector,
     },
     // Exception 3: Hard Fault Interrupt.
     Vector {
-        handler: HardFaultTrampoline,
+        handler: __hard_fault_trampoline,
     },
     // Exception 4: Memory Management Interrupt [not on Cortex-M0 variants].
     #[cfg(not(armv6m))]
     Vector {
-        handler: MemoryManagement,
+        handler: __memory_management_vector,
     },
     #[cfg(armv6m)]
     Vector { reserved: 0 },
     // Exception 5: Bus Fault Interrupt [not on Cortex-M0 variants].
     #[cfg(not(armv6m))]
-    Vector { handler: BusFault },
+    Vector {
+        handler: __bus_fault_vector,
+    },
     #[cfg(armv6m)]
     Vector { reserved: 0 },
     // Exception 6: Usage Fault Interrupt [not on Cortex-M0 variants].
     #[cfg(not(armv6m))]
     Vector {
-        handler: UsageFault,
+        handler: __usage_fault_vector,
     },
     #[cfg(armv6m)]
     Vector { reserved: 0 },
     // Exception 7: Secure Fault Interrupt [only on Armv8-M].
     #[cfg(armv8m)]
     Vector {
-        handler: SecureFault,
+        handler: __secure_fault_vector,
     },
     #[cfg(not(armv8m))]
     Vector { reserved: 0 },
@@ -1051,7 +1805,9 @@ pub static __EXCEPTIONS: [Vector; 14] = [
     Vector { reserved: 0 },
     Vector { reserved: 0 },
     // Exception 11: SV Call Interrupt.
-    Vector { handler: SVCall },
+    Vector {
+        handler: __sv_call_vector,
+    },
     // Exception 12: Debug Monitor Interrupt [not on Cortex-M0 variants].
     #[cfg(not(armv6m))]
     Vector {
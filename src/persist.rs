@@ -0,0 +1,215 @@
+//! Data that survives a reset
+
+use core::mem::{self, MaybeUninit};
+use core::ptr;
+use core::sync::atomic::{self, Ordering};
+
+/// Magic word marking a `Persistent` value as written by `Persistent::set`
+const MAGIC: u32 = 0x5045_5253;
+
+// FNV-1a parameters
+const FNV_OFFSET_BASIS: u32 = 0x811c_9dc5;
+const FNV_PRIME: u32 = 0x0100_0193;
+
+/// Types that can be stored in a [`Persistent`](struct.Persistent.html)
+///
+/// The value read back after a reset is made of whatever bytes were left in RAM, which passed the
+/// checksum but may have been written by a different version of the program.
+///
+/// # Safety
+///
+/// Any bit pattern must be a valid value of the type, and the type must not contain padding bytes
+/// (which are included in the checksum). In practice this means integers, floats, arrays of those
+/// and `#[repr(C)]` structs of those whose fields leave no gaps.
+///
+/// # Examples
+///
+/// ``` no_run
+/// use cortex_m_rt::PersistentData;
+///
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct ResetInfo {
+///     count: u32,
+///     reason: u32,
+/// }
+///
+/// unsafe impl PersistentData for ResetInfo {}
+/// ```
+pub unsafe trait PersistentData: Copy {}
+
+macro_rules! persistent_data {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl PersistentData for $ty {}
+        )*
+    };
+}
+
+persistent_data!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+macro_rules! persistent_data_arrays {
+    ($($n:expr),*) => {
+        $(
+            unsafe impl<T> PersistentData for [T; $n] where T: PersistentData {}
+        )*
+    };
+}
+
+persistent_data_arrays!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 64, 128, 256, 512, 1024
+);
+
+/// A value that survives a (warm) reset, along with a marker that tells whether it's valid
+///
+/// The contents of RAM are not defined after a power-on reset, but they are retained across a
+/// warm reset (e.g. one triggered by software, by the watchdog or by the reset pin). `Persistent`
+/// stores a magic word and a checksum next to the value so that, after a reset, the program can
+/// tell whether the value was written before the reset or whether it is just garbage.
+///
+/// A `Persistent` must be placed in the `.persist` section, which is neither zeroed nor
+/// initialized by the `Reset` handler. This is done by declaring it as a `static mut` variable at
+/// the beginning of an [`#[entry]`](attr.entry.html), [`#[exception]`](attr.exception.html) or
+/// `#[interrupt]` function and marking it with the `#[persist]` attribute.
+///
+/// The stored type must implement [`PersistentData`](trait.PersistentData.html): the memory
+/// holding the value may contain any bit pattern after a reset. If the value is shared with another
+/// image, like a bootloader, both images must agree on its layout.
+///
+/// Note that some devices clear their RAM on reset or require it to be initialized before it's
+/// read (e.g. RAM with ECC); those RAM banks can't be used to hold `Persistent` values.
+///
+/// # Examples
+///
+/// ``` no_run
+/// # #![no_main]
+/// # use cortex_m_rt::entry;
+/// use cortex_m_rt::Persistent;
+///
+/// #[entry]
+/// fn main() -> ! {
+///     #[persist]
+///     static mut BOOT_COUNT: Persistent<u32> = Persistent::uninit();
+///
+///     let count = BOOT_COUNT.get().cloned().unwrap_or(0);
+///     BOOT_COUNT.set(count + 1);
+///
+///     loop {
+///         /* .. */
+///     }
+/// }
+/// ```
+#[repr(C)]
+pub struct Persistent<T> {
+    magic: u32,
+    checksum: u32,
+    value: MaybeUninit<T>,
+}
+
+impl<T> Persistent<T> {
+    /// Creates a `Persistent` whose contents are left as they were before the reset
+    ///
+    /// This is only meant to be used as the initializer of a `#[persist]` variable.
+    pub const fn uninit() -> Self {
+        Persistent {
+            magic: 0,
+            checksum: 0,
+            value: MaybeUninit::uninit(),
+        }
+    }
+}
+
+impl<T> Persistent<T>
+where
+    T: PersistentData,
+{
+    /// Returns `true` if the value was written using `set` before the last reset
+    pub fn is_valid(&self) -> bool {
+        unsafe {
+            ptr::read_volatile(&self.magic) == MAGIC
+                && ptr::read_volatile(&self.checksum) == self.compute_checksum()
+        }
+    }
+
+    /// Returns a reference to the value, if it's valid
+    pub fn get(&self) -> Option<&T> {
+        if self.is_valid() {
+            Some(unsafe { &*self.value.as_ptr() })
+        } else {
+            None
+        }
+    }
+
+    /// Stores `value` and marks it as valid
+    pub fn set(&mut self, value: T) {
+        self.invalidate();
+
+        self.value = MaybeUninit::new(value);
+        let checksum = self.compute_checksum();
+        unsafe {
+            ptr::write_volatile(&mut self.checksum, checksum);
+        }
+
+        // the value and its checksum must be in memory before it's marked as valid
+        atomic::compiler_fence(Ordering::SeqCst);
+        unsafe {
+            ptr::write_volatile(&mut self.magic, MAGIC);
+        }
+    }
+
+    /// Writes a new value in place and marks it as valid
+    ///
+    /// The value is zeroed before being passed to `init`. Unlike `set`, this doesn't need to
+    /// construct the value on the stack, which matters for large values written from fault
+    /// handlers.
+    ///
+    /// # Safety
+    ///
+    /// The all-zeros bit pattern must be a valid value of type `T`.
+    #[cfg(feature = "crash-record")]
+    pub(crate) unsafe fn set_with<F>(&mut self, init: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        self.invalidate();
+
+        ptr::write_bytes(self.value.as_mut_ptr(), 0, 1);
+        init(&mut *self.value.as_mut_ptr());
+        let checksum = self.compute_checksum();
+        ptr::write_volatile(&mut self.checksum, checksum);
+
+        // the value and its checksum must be in memory before it's marked as valid
+        atomic::compiler_fence(Ordering::SeqCst);
+        ptr::write_volatile(&mut self.magic, MAGIC);
+    }
+
+    /// Returns the value, if it's valid, and marks it as invalid
+    pub fn take(&mut self) -> Option<T> {
+        let value = self.get().cloned();
+        self.invalidate();
+        value
+    }
+
+    /// Marks the value as invalid
+    pub fn invalidate(&mut self) {
+        unsafe {
+            ptr::write_volatile(&mut self.magic, 0);
+        }
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+
+    /// Computes the FNV-1a hash of the bytes of the value, seeded with its size
+    fn compute_checksum(&self) -> u32 {
+        let size = mem::size_of::<T>();
+        let bytes = self.value.as_ptr() as *const u8;
+
+        let mut hash = FNV_OFFSET_BASIS ^ size as u32;
+        for i in 0..size {
+            // volatile: the compiler must not assume anything about the contents of the memory
+            let byte = unsafe { ptr::read_volatile(bytes.add(i)) };
+            hash = (hash ^ u32::from(byte)).wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+}
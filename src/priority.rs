@@ -0,0 +1,76 @@
+//! Priorities set using the `#[exception]` and `#[interrupt]` attributes
+
+use core::{mem, ptr, slice};
+
+/// System Handler Priority Registers, starting at the priority of exception number 4
+const SCB_SHPR: usize = 0xE000_ED18;
+/// Interrupt Priority Registers
+const NVIC_IPR: usize = 0xE000_E400;
+/// Interrupt Set-Enable Registers
+const NVIC_ISER: usize = 0xE000_E100;
+
+/// An entry of the priority table
+#[doc(hidden)]
+#[repr(C)]
+pub struct Priority {
+    pub irqn: i16,
+    pub priority: u8,
+    pub enable: bool,
+}
+
+/// Returns the entries of the priority table
+fn priorities() -> &'static [Priority] {
+    extern "C" {
+        static __spriorities: Priority;
+        static __epriorities: Priority;
+    }
+
+    unsafe {
+        let start = &__spriorities as *const Priority;
+        let end = &__epriorities as *const Priority;
+
+        slice::from_raw_parts(
+            start,
+            (end as usize - start as usize) / mem::size_of::<Priority>(),
+        )
+    }
+}
+
+/// Sets the priority of every exception and interrupt in the priority table, and enables the
+/// interrupts that must be enabled
+///
+/// Priorities that don't fit in `__nvic_prio_bits` are rejected by the linker; this panics if a
+/// priority doesn't fit in the priority bits the device actually implements.
+pub(crate) unsafe fn apply() {
+    for entry in priorities() {
+        let addr = if entry.irqn < 0 {
+            SCB_SHPR + (i32::from(entry.irqn) + 16 - 4) as usize
+        } else {
+            NVIC_IPR + entry.irqn as usize
+        };
+
+        // ARMv6-M only supports word accesses to the priority registers
+        let word = (addr & !3) as *mut u32;
+        let shift = (addr & 3) * 8;
+        let mask = 0xff << shift;
+
+        // the priority bits that aren't implemented read as zero; these are the least significant
+        // bits of the priority field
+        let value = ptr::read_volatile(word);
+        ptr::write_volatile(word, value | mask);
+        let bits = ((ptr::read_volatile(word) & mask) >> shift).count_ones();
+
+        if u32::from(entry.priority) >= 1 << bits {
+            ptr::write_volatile(word, value);
+            panic!("priority doesn't fit in the priority bits implemented by the device");
+        }
+
+        let priority = u32::from(entry.priority) << (8 - bits);
+        ptr::write_volatile(word, (value & !mask) | (priority << shift));
+
+        if entry.enable && entry.irqn >= 0 {
+            let irqn = entry.irqn as usize;
+            ptr::write_volatile((NVIC_ISER + (irqn / 32) * 4) as *mut u32, 1 << (irqn % 32));
+        }
+    }
+}
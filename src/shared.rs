@@ -0,0 +1,55 @@
+//! Interrupt handlers declared using `#[interrupt(shared)]`
+
+use core::{mem, ptr, slice};
+
+/// Interrupt Control and State Register
+const SCB_ICSR: usize = 0xE000_ED04;
+
+/// An entry of the table of shared interrupt handlers
+#[doc(hidden)]
+#[repr(C)]
+pub struct SharedHandler {
+    pub irqn: i16,
+    pub handler: unsafe extern "C" fn(),
+}
+
+/// Returns the entries of the table of shared interrupt handlers, in link order
+fn handlers() -> &'static [SharedHandler] {
+    extern "C" {
+        static __sshared_interrupts: SharedHandler;
+        static __eshared_interrupts: SharedHandler;
+    }
+
+    unsafe {
+        let start = &__sshared_interrupts as *const SharedHandler;
+        let end = &__eshared_interrupts as *const SharedHandler;
+
+        slice::from_raw_parts(
+            start,
+            (end as usize - start as usize) / mem::size_of::<SharedHandler>(),
+        )
+    }
+}
+
+/// Returns the IRQ number of the exception or interrupt being serviced
+#[inline]
+pub(crate) fn active_irqn() -> i16 {
+    unsafe { (ptr::read_volatile(SCB_ICSR as *const u32) & 0x1FF) as i16 - 16 }
+}
+
+/// Calls, in link order, all the shared handlers of interrupt `irqn`
+///
+/// Returns `false` if the interrupt has no shared handlers.
+#[doc(hidden)]
+pub unsafe fn dispatch_shared(irqn: i16) -> bool {
+    let mut dispatched = false;
+
+    for shared in handlers() {
+        if shared.irqn == irqn {
+            (shared.handler)();
+            dispatched = true;
+        }
+    }
+
+    dispatched
+}
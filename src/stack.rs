@@ -0,0 +1,58 @@
+//! Stack usage measurement
+//!
+//! When the `paint-stack` feature is enabled the `Reset` handler fills the whole stack, from
+//! `_stack_end` up to `_stack_start`, with [`PAINT_PATTERN`] before calling the program entry
+//! point. The functions in this module scan the stack for that pattern to find out how much of it
+//! has been used so far.
+//!
+//! [`PAINT_PATTERN`]: constant.PAINT_PATTERN.html
+//!
+//! Note that the measurement is a heuristic: a word that the program pushed onto the stack but
+//! that happens to be equal to `PAINT_PATTERN` can't be told apart from an unused one, so the
+//! stack usage may be underestimated by a few words.
+
+use core::ptr;
+
+/// The value the stack is painted with
+pub const PAINT_PATTERN: u32 = 0xcccc_cccc;
+
+/// Returns the painted region, from the lowest to the highest address
+#[inline]
+fn bounds() -> (*const u32, *const u32) {
+    extern "C" {
+        static __spaint: u32;
+        static __epaint: u32;
+    }
+
+    unsafe { (&__spaint as *const u32, &__epaint as *const u32) }
+}
+
+/// Returns the size of the stack in bytes
+#[inline]
+pub fn size() -> usize {
+    let (end, start) = bounds();
+
+    start as usize - end as usize
+}
+
+/// Returns the number of bytes of stack that have never been used since the last reset
+///
+/// The stack is scanned from its end (lowest address) upwards until a word that doesn't match
+/// the paint pattern is found, so the cost of this function is proportional to the amount of
+/// unused stack.
+pub fn unused_bytes() -> usize {
+    let (end, start) = bounds();
+
+    let mut p = end;
+    while p < start && unsafe { ptr::read_volatile(p) } == PAINT_PATTERN {
+        p = unsafe { p.add(1) };
+    }
+
+    p as usize - end as usize
+}
+
+/// Returns the maximum number of bytes of stack that have been used since the last reset
+#[inline]
+pub fn high_water_mark() -> usize {
+    size() - unused_bytes()
+}
@@ -0,0 +1,135 @@
+//! Supervisor calls
+//!
+//! When the `syscalls` feature is enabled the runtime provides the `SVCall` handler, which
+//! dispatches every `svc #n` instruction to the function registered for number `n` using the
+//! [`#[syscall(n)]`][attr-syscall] attribute. The registered functions are collected by the linker
+//! into the `.syscalls` section, a table sorted by number, so no central list of syscalls is
+//! needed.
+//!
+//! [attr-syscall]: ../attr.syscall.html
+//!
+//! Supervisor calls are made using the [`svc_call!`](../macro.svc_call.html) macro, which doesn't
+//! depend on the feature; it can also be used with a user defined `SVCall` handler, or to call into
+//! a kernel that's part of a different image.
+
+#[cfg(feature = "syscalls")]
+use core::slice;
+
+#[cfg(feature = "syscalls")]
+use {svc_number, ExceptionFrame};
+
+/// An entry of the syscall table
+#[cfg(feature = "syscalls")]
+#[doc(hidden)]
+#[repr(C)]
+pub struct Syscall {
+    pub number: u32,
+    pub handler: unsafe extern "C" fn(u32, u32, u32, u32) -> u32,
+}
+
+/// Returns the entries of the syscall table, sorted by number
+#[cfg(feature = "syscalls")]
+fn syscalls() -> &'static [Syscall] {
+    extern "C" {
+        static __ssyscalls: Syscall;
+        static __esyscalls: Syscall;
+    }
+
+    unsafe {
+        let start = &__ssyscalls as *const Syscall;
+        let end = &__esyscalls as *const Syscall;
+
+        slice::from_raw_parts(start, (end as usize - start as usize) / 8)
+    }
+}
+
+/// Dispatches a supervisor call to the function registered for its number
+///
+/// The arguments are taken from the stacked `r0`-`r3` and the return value is written back to the
+/// stacked `r0`. Numbers without a registered function are handled by `DefaultHandler`.
+#[cfg(feature = "syscalls")]
+#[doc(hidden)]
+#[export_name = "SVCall"]
+pub unsafe extern "C" fn dispatch(frame: &mut ExceptionFrame) {
+    extern "C" {
+        fn DefaultHandler();
+    }
+
+    let number = u32::from(svc_number(frame));
+    let syscalls = syscalls();
+    match syscalls.binary_search_by_key(&number, |syscall| syscall.number) {
+        Ok(i) => {
+            let ret = (syscalls[i].handler)(frame.r0(), frame.r1(), frame.r2(), frame.r3());
+            frame.set_r0(ret);
+        }
+        Err(_) => DefaultHandler(),
+    }
+}
+
+/// Executes `svc #number` with the given arguments in `r0`-`r3` and returns the value of `r0` after
+/// the supervisor call
+#[doc(hidden)]
+#[inline(always)]
+pub unsafe fn svc_call(number: u8, a0: u32, a1: u32, a2: u32, a3: u32) -> u32 {
+    extern "C" {
+        fn __cortex_m_rt_svc(a0: u32, a1: u32, a2: u32, a3: u32, number: u32) -> u32;
+    }
+
+    __cortex_m_rt_svc(a0, a1, a2, a3, u32::from(number))
+}
+
+/// Makes a supervisor call
+///
+/// `svc_call!(n, a0, a1, ..)` executes the `svc #n` instruction with up to four `u32` arguments in
+/// registers `r0`-`r3`, and evaluates to the `u32` the `SVCall` handler left in `r0`. `n` must be
+/// in the range `0..=255`. The arguments are evaluated before the call, outside of any `unsafe`
+/// block.
+///
+/// With the `syscalls` feature this calls the function registered using `#[syscall(n)]`; otherwise
+/// the call is handled by the `SVCall` handler of the program (see [`#[exception]`][attr-exception]).
+///
+/// [attr-exception]: attr.exception.html
+///
+/// Note that supervisor calls can only be made from thread mode or from an exception handler with a
+/// lower priority than `SVCall`; otherwise the `svc` instruction causes a HardFault.
+///
+/// # Examples
+///
+/// ``` ignore
+/// use cortex_m_rt::svc_call;
+///
+/// let sum = svc_call!(1, 2, 3);
+/// ```
+#[macro_export]
+macro_rules! svc_call {
+    ($number:expr) => {{
+        let number: u8 = $number;
+        unsafe { $crate::svc_call(number, 0, 0, 0, 0) }
+    }};
+    ($number:expr, $a0:expr) => {{
+        let number: u8 = $number;
+        let a0: u32 = $a0;
+        unsafe { $crate::svc_call(number, a0, 0, 0, 0) }
+    }};
+    ($number:expr, $a0:expr, $a1:expr) => {{
+        let number: u8 = $number;
+        let a0: u32 = $a0;
+        let a1: u32 = $a1;
+        unsafe { $crate::svc_call(number, a0, a1, 0, 0) }
+    }};
+    ($number:expr, $a0:expr, $a1:expr, $a2:expr) => {{
+        let number: u8 = $number;
+        let a0: u32 = $a0;
+        let a1: u32 = $a1;
+        let a2: u32 = $a2;
+        unsafe { $crate::svc_call(number, a0, a1, a2, 0) }
+    }};
+    ($number:expr, $a0:expr, $a1:expr, $a2:expr, $a3:expr) => {{
+        let number: u8 = $number;
+        let a0: u32 = $a0;
+        let a1: u32 = $a1;
+        let a2: u32 = $a2;
+        let a3: u32 = $a3;
+        unsafe { $crate::svc_call(number, a0, a1, a2, a3) }
+    }};
+}
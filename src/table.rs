@@ -0,0 +1,184 @@
+//! Read-only view of the vector table
+
+use core::{fmt, slice};
+
+use Exception;
+
+/// The vector table of the program, as placed in the `.vector_table` section
+///
+/// Returned by [`vector_table`](fn.vector_table.html).
+#[derive(Clone, Copy)]
+pub struct VectorTable {
+    words: &'static [usize],
+}
+
+/// An exception or interrupt vector of a [`VectorTable`](struct.VectorTable.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VectorEntry {
+    irqn: i16,
+    handler: usize,
+}
+
+/// Iterator over the vectors of a [`VectorTable`](struct.VectorTable.html)
+///
+/// Returned by [`VectorTable::entries`](struct.VectorTable.html#method.entries).
+pub struct VectorEntries {
+    table: VectorTable,
+    index: usize,
+}
+
+/// Returns a read-only view of the vector table of the program
+///
+/// This is the table in the `.vector_table` section, the one the linker put together. It is not
+/// necessarily the table the processor is using: that one is pointed to by VTOR, which may have
+/// been changed at runtime.
+///
+/// # Examples
+///
+/// ``` ignore
+/// use cortex_m_rt::vector_table;
+///
+/// let table = vector_table();
+/// for entry in table.entries() {
+///     if entry.irqn() >= 0 && table.is_default(entry.irqn()) {
+///         // interrupt `entry.irqn()` is handled by `DefaultHandler`
+///     }
+/// }
+/// ```
+pub fn vector_table() -> VectorTable {
+    extern "C" {
+        static __svector_table: usize;
+        static __evector_table: usize;
+    }
+
+    unsafe {
+        let start = &__svector_table as *const usize;
+        let end = &__evector_table as *const usize;
+
+        VectorTable {
+            words: slice::from_raw_parts(start, (end as usize - start as usize) / 4),
+        }
+    }
+}
+
+impl VectorTable {
+    /// Returns the initial value of the main stack pointer, the first word of the table
+    pub fn initial_sp(&self) -> u32 {
+        self.words[0] as u32
+    }
+
+    /// Returns the address of the `Reset` handler, the second word of the table
+    pub fn reset_vector(&self) -> usize {
+        self.words[1]
+    }
+
+    /// Returns the number of device specific interrupts in the table
+    pub fn interrupts(&self) -> usize {
+        self.words.len() - 16
+    }
+
+    /// Returns the vector of the exception or interrupt with IRQ number `irqn`
+    ///
+    /// Returns `None` if `irqn` is not in the table or if its vector is reserved.
+    pub fn entry(&self, irqn: i16) -> Option<VectorEntry> {
+        let index = i32::from(irqn) + 16;
+
+        if index < 1 || index as usize >= self.words.len() {
+            return None;
+        }
+
+        let index = index as usize;
+        let handler = self.words[index];
+
+        if handler == 0 {
+            None
+        } else {
+            Some(VectorEntry { irqn, handler })
+        }
+    }
+
+    /// Returns `true` if the exception or interrupt with IRQ number `irqn` is handled by
+    /// `DefaultHandler`, i.e. if no handler was defined for it
+    ///
+    /// Returns `false` for reserved vectors and for IRQ numbers that are not in the table. Note
+    /// that interrupts with handlers declared using `#[interrupt(shared)]` are handled by
+    /// `DefaultHandler` too.
+    pub fn is_default(&self, irqn: i16) -> bool {
+        extern "C" {
+            fn DefaultHandler();
+        }
+
+        let default: unsafe extern "C" fn() = DefaultHandler;
+
+        match self.entry(irqn) {
+            Some(entry) => entry.handler == default as usize,
+            None => false,
+        }
+    }
+
+    /// Returns an iterator over the vectors of the table, except for the reserved ones, starting
+    /// with the `Reset` handler
+    pub fn entries(&self) -> VectorEntries {
+        VectorEntries {
+            table: *self,
+            index: 1,
+        }
+    }
+}
+
+impl fmt::Debug for VectorTable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VectorTable")
+            .field("initial_sp", &self.initial_sp())
+            .field("reset_vector", &self.reset_vector())
+            .field("interrupts", &self.interrupts())
+            .finish()
+    }
+}
+
+impl VectorEntry {
+    /// Returns the IRQ number of the vector (see CMSIS)
+    ///
+    /// This is a negative number for core exceptions (e.g. `-1` for `SysTick`) and a non-negative
+    /// number for device specific interrupts.
+    pub fn irqn(&self) -> i16 {
+        self.irqn
+    }
+
+    /// Returns `true` if this is the vector of a device specific interrupt
+    pub fn is_interrupt(&self) -> bool {
+        self.irqn >= 0
+    }
+
+    /// Returns the exception this is the vector of, or `None` if this is the vector of an interrupt
+    /// or of `Reset`
+    pub fn exception(&self) -> Option<Exception> {
+        Exception::from_irqn(self.irqn)
+    }
+
+    /// Returns the address of the handler
+    ///
+    /// For `HardFault`, and for the exceptions whose handler takes the exception frame, this is the
+    /// address of the trampoline that passes the frame to the handler. Like any address of Thumb
+    /// code, it has its least significant bit set.
+    pub fn handler(&self) -> usize {
+        self.handler
+    }
+}
+
+impl Iterator for VectorEntries {
+    type Item = VectorEntry;
+
+    fn next(&mut self) -> Option<VectorEntry> {
+        while self.index < self.table.words.len() {
+            let irqn = self.index as i16 - 16;
+            self.index += 1;
+
+            if let Some(entry) = self.table.entry(irqn) {
+                return Some(entry);
+            }
+        }
+
+        None
+    }
+}
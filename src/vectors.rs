@@ -0,0 +1,118 @@
+//! Handlers installed at runtime
+//!
+//! When the `ram-vector-table` feature is enabled `Reset` copies the vector table from FLASH into
+//! the `.vector_table_ram` section and points the Vector Table Offset Register (VTOR) to that
+//! copy, before calling `main`. The functions in this module then change the entries of the copy,
+//! which makes it possible to install exception and interrupt handlers at runtime, e.g. from a
+//! driver that's loaded as a plugin.
+//!
+//! Vectors are identified by their IRQ number, as in CMSIS: device specific interrupts have
+//! non-negative numbers (e.g. `Interrupt::USART1 as i16` for an `svd2rust` generated device crate)
+//! and exceptions have negative numbers (e.g. `-1` is `SysTick` and `-2` is `PendSV`).
+//!
+//! Note that the Cortex-M0 and the Cortex-M1 don't implement VTOR, and on other ARMv6-M cores it's
+//! optional, so this feature can't be enabled when building for a `thumbv6m-` target.
+//!
+//! # Examples
+//!
+//! ``` ignore
+//! use cortex_m_rt::vectors;
+//!
+//! extern "C" fn on_tick() {
+//!     // ..
+//! }
+//!
+//! // SysTick
+//! vectors::set_handler(-1, on_tick);
+//!
+//! // ..
+//!
+//! // back to the handler that was bound at link time
+//! vectors::restore_default(-1);
+//! ```
+
+use core::sync::atomic::{self, Ordering};
+use core::{ptr, slice};
+
+/// Returns the start and the length, in words, of the copy of the vector table in RAM
+#[inline]
+fn ram_table() -> (*mut usize, usize) {
+    extern "C" {
+        static mut __svector_table_ram: usize;
+        static mut __evector_table_ram: usize;
+    }
+
+    let (start, end) = unsafe {
+        (
+            &mut __svector_table_ram as *mut usize,
+            &mut __evector_table_ram as *mut usize,
+        )
+    };
+
+    (start, (end as usize - start as usize) / 4)
+}
+
+/// Returns the vector table in FLASH
+#[inline]
+fn flash_table() -> &'static [usize] {
+    extern "C" {
+        static __svector_table: usize;
+        static __evector_table: usize;
+    }
+
+    unsafe {
+        let start = &__svector_table as *const usize;
+        let end = &__evector_table as *const usize;
+
+        slice::from_raw_parts(start, (end as usize - start as usize) / 4)
+    }
+}
+
+/// Returns the position of the handler of `irqn` in the vector table
+///
+/// # Panics
+///
+/// If `irqn` is not in the vector table, or if it refers to the reset vector.
+fn index(irqn: i16) -> usize {
+    let index = i32::from(irqn) + 16;
+
+    if index < 2 || index as usize >= ram_table().1 {
+        panic!("no vector with IRQ number {} can be set", irqn);
+    }
+
+    index as usize
+}
+
+/// Writes entry `index` of the RAM vector table
+fn write(index: usize, vector: usize) {
+    unsafe {
+        ptr::write_volatile(ram_table().0.add(index), vector);
+    }
+
+    // make sure the new vector is in memory before the caller, e.g., enables the interrupt
+    atomic::fence(Ordering::SeqCst);
+}
+
+/// Installs `handler` as the handler of the exception or interrupt with IRQ number `irqn`
+///
+/// The handler is used the next time the exception or interrupt is taken, unless it's already
+/// pending, in which case either handler may be used.
+///
+/// # Panics
+///
+/// If `irqn` is not in the vector table, or if it refers to the reset vector.
+pub fn set_handler(irqn: i16, handler: extern "C" fn()) {
+    write(index(irqn), handler as usize);
+}
+
+/// Installs the handler that was bound at link time back as the handler of the exception or
+/// interrupt with IRQ number `irqn`
+///
+/// # Panics
+///
+/// If `irqn` is not in the vector table, or if it refers to the reset vector.
+pub fn restore_default(irqn: i16) {
+    let index = index(irqn);
+
+    write(index, flash_table()[index]);
+}
@@ -13,4 +13,4 @@ fn foo() -> ! {
 
 #[exception]
 unsafe fn DefaultHandler(_irqn: i16, undef: u32) {}
-//~^ ERROR `DefaultHandler` must have signature `unsafe fn(i16) [-> !]`
+//~^ ERROR `DefaultHandler` must have signature `unsafe fn(i16) [-> !]` or `unsafe fn(ExceptionOrIrq) [-> !]`
@@ -13,6 +13,6 @@ fn foo() -> ! {
 
 #[exception]
 unsafe fn DefaultHandler(_irqn: i16) -> u32 {
-    //~^ ERROR `DefaultHandler` must have signature `unsafe fn(i16) [-> !]`
+    //~^ ERROR `DefaultHandler` must have signature `unsafe fn(i16) [-> !]` or `unsafe fn(ExceptionOrIrq) [-> !]`
     0
 }
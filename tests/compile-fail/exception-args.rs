@@ -11,5 +11,5 @@ fn foo() -> ! {
     loop {}
 }
 
-#[exception(SysTick)] //~ ERROR This attribute accepts no arguments
+#[exception(SysTick, PendSV)] //~ ERROR This attribute only accepts an exception name and a `priority = <number>` argument
 fn SysTick() {}
@@ -0,0 +1,20 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+fn UsageFault(_ef: &ExceptionFrame, _undef: u32) {}
+//~^ ERROR `UsageFault` handler must have signature `[unsafe] fn() [-> !]` or `[unsafe] fn(&[mut] ExceptionFrame) [-> !]`
+
+#[exception]
+fn SysTick(_ef: &ExceptionFrame) {}
+//~^ ERROR `#[exception]` handlers other than `DefaultHandler` and `HardFault` must have signature `[unsafe] fn() [-> !]`
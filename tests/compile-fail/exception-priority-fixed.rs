@@ -0,0 +1,15 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception(priority = 1)] //~ ERROR the priority of `NonMaskableInt` can't be configured
+unsafe fn NonMaskableInt() {}
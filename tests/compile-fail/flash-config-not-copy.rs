@@ -0,0 +1,19 @@
+#![no_main]
+#![no_std]
+
+#[macro_use(flash_config)]
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::sync::atomic::AtomicUsize;
+
+use cortex_m_rt::entry;
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+flash_config! {
+    static CONFIG: AtomicUsize = AtomicUsize::new(0); //~ ERROR the trait bound `AtomicUsize: Copy` is not satisfied
+}
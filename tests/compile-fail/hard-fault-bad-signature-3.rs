@@ -0,0 +1,16 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+unsafe fn HardFault(_ef: &ExceptionFrame) {}
+//~^ ERROR `HardFault` handler must have signature `unsafe fn(&ExceptionFrame) -> !` or `unsafe fn(&ExceptionFrame, &FaultInfo) -> !`, or, if it returns, `unsafe fn(&mut ExceptionFrame)` or `unsafe fn(&mut ExceptionFrame, &FaultInfo)`
@@ -16,5 +16,5 @@ enum interrupt {
     USART1,
 }
 
-#[interrupt(true)] //~ ERROR This attribute accepts no arguments
+#[interrupt(true)] //~ ERROR This attribute only accepts interrupt names and the `priority = <number>`, `enable` and `shared` arguments
 fn USART1() {}
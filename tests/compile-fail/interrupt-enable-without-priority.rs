@@ -0,0 +1,20 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, interrupt};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[allow(non_camel_case_types)]
+enum interrupt {
+    USART1,
+}
+
+#[interrupt(enable)] //~ ERROR `enable` requires a `priority = <number>` argument
+fn USART1() {}
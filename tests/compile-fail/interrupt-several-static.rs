@@ -0,0 +1,25 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, interrupt};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[allow(non_camel_case_types)]
+enum interrupt {
+    USART1,
+    USART2,
+}
+
+#[interrupt(USART1, USART2)]
+fn uart_irq() {
+    static mut COUNT: u32 = 0; //~ ERROR `static mut` variables can't be declared in a handler bound to several interrupts
+
+    *COUNT += 1;
+}
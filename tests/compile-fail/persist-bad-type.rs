@@ -0,0 +1,34 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::mem::MaybeUninit;
+use cortex_m_rt::{entry, exception, interrupt, Persistent};
+
+#[allow(non_camel_case_types)]
+enum interrupt {
+    UART0,
+}
+
+#[entry]
+fn foo() -> ! {
+    #[persist]
+    static mut X: MaybeUninit<u32> = MaybeUninit::uninit(); //~ ERROR `#[persist]` variables must have type `Persistent<T>`
+
+    loop {}
+}
+
+#[exception]
+fn SVCall() {
+    #[persist]
+    static mut X: Persistent<u32> = Persistent::new(0); //~ ERROR `#[persist]` variables must be initialized with `Persistent::uninit()`
+}
+
+#[interrupt]
+fn UART0() {
+    #[persist]
+    #[uninit] //~ ERROR a variable can only be placed in one section
+    static mut X: Persistent<u32> = Persistent::uninit();
+}
@@ -0,0 +1,17 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, Persistent};
+
+#[entry]
+fn foo() -> ! {
+    #[persist]
+    static mut X: Persistent<bool> = Persistent::uninit();
+
+    let _ = X.get(); //~ ERROR the method `get` exists
+
+    loop {}
+}
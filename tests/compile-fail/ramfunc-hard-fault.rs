@@ -0,0 +1,18 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ramfunc, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+#[ramfunc] //~ ERROR The `HardFault` handler can't be placed in RAM
+unsafe fn HardFault(_ef: &ExceptionFrame) -> ! {
+    loop {}
+}
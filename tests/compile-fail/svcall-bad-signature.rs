@@ -0,0 +1,16 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, ExceptionFrame};
+
+#[entry]
+fn foo() -> ! {
+    loop {}
+}
+
+#[exception]
+fn SVCall(_num: u32, _frame: &mut ExceptionFrame) {}
+//~^ ERROR `SVCall` handler must have signature `[unsafe] fn() [-> !]` or `[unsafe] fn(u8, &[mut] ExceptionFrame) [-> !]`
@@ -0,0 +1,33 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use core::mem::MaybeUninit;
+use cortex_m_rt::{entry, exception, interrupt};
+
+#[allow(non_camel_case_types)]
+enum interrupt {
+    UART0,
+}
+
+#[entry]
+fn foo() -> ! {
+    #[uninit]
+    static mut X: MaybeUninit<u32> = MaybeUninit::new(0); //~ ERROR `#[uninit]` variables must be initialized with `MaybeUninit::uninit()`
+
+    loop {}
+}
+
+#[exception]
+fn SVCall() {
+    #[uninit]
+    static mut X: MaybeUninit<u32> = MaybeUninit::new(0); //~ ERROR `#[uninit]` variables must be initialized with `MaybeUninit::uninit()`
+}
+
+#[interrupt]
+fn UART0() {
+    #[uninit]
+    static mut X: MaybeUninit<u32> = MaybeUninit::new(0); //~ ERROR `#[uninit]` variables must be initialized with `MaybeUninit::uninit()`
+}
@@ -0,0 +1,32 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::{entry, exception, interrupt};
+
+#[allow(non_camel_case_types)]
+enum interrupt {
+    UART0,
+}
+
+#[entry]
+fn foo() -> ! {
+    #[uninit]
+    static mut X: u32 = 0; //~ ERROR `#[uninit]` variables must have type `MaybeUninit<T>` or `[MaybeUninit<T>; N]`
+
+    loop {}
+}
+
+#[exception]
+fn SVCall() {
+    #[uninit]
+    static mut X: [u8; 4] = [0; 4]; //~ ERROR `#[uninit]` variables must have type `MaybeUninit<T>` or `[MaybeUninit<T>; N]`
+}
+
+#[interrupt]
+fn UART0() {
+    #[uninit]
+    static mut X: u32 = 0; //~ ERROR `#[uninit]` variables must have type `MaybeUninit<T>` or `[MaybeUninit<T>; N]`
+}
@@ -0,0 +1,26 @@
+#![no_main]
+#![no_std]
+
+extern crate cortex_m_rt;
+extern crate panic_halt;
+
+use cortex_m_rt::entry;
+
+// not `core::mem::MaybeUninit`
+struct MaybeUninit<T> {
+    value: T,
+}
+
+impl MaybeUninit<bool> {
+    const fn uninit() -> Self {
+        MaybeUninit { value: true }
+    }
+}
+
+#[entry]
+fn foo() -> ! {
+    #[uninit]
+    static mut X: MaybeUninit<bool> = MaybeUninit::uninit(); //~ ERROR mismatched types
+
+    loop {}
+}